@@ -0,0 +1,171 @@
+use std::str::FromStr;
+
+use anchor_lang::{prelude::*, solana_program::keccak};
+
+use crate::metaplex::{Collection, Creator, TokenStandard, Uses};
+
+pub fn bubblegum_program_id() -> Pubkey {
+    Pubkey::from_str("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY").unwrap()
+}
+
+pub fn compression_program_id() -> Pubkey {
+    Pubkey::from_str("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK").unwrap()
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone)]
+pub enum TokenProgramVersion {
+    Original,
+    Token2022,
+}
+
+/// Metadata of a compressed NFT, as hashed into its Bubblegum leaf
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug, Clone)]
+pub struct MetadataArgs {
+    /// The name of the asset
+    pub name: String,
+    /// The symbol for the asset
+    pub symbol: String,
+    /// URI pointing to JSON representing the asset
+    pub uri: String,
+    /// Royalty basis points that goes to creators in secondary sales (0-10000)
+    pub seller_fee_basis_points: u16,
+    // Immutable, once flipped, all sales of this metadata are considered secondary.
+    pub primary_sale_happened: bool,
+    // Whether or not the data struct is mutable, default is not
+    pub is_mutable: bool,
+    /// nonce for easy calculation of editions, if present
+    pub edition_nonce: Option<u8>,
+    /// Since we cannot easily change Metadata, we add the new DataV2 fields here at the end.
+    pub token_standard: Option<TokenStandard>,
+    /// Collection
+    pub collection: Option<Collection>,
+    /// Uses
+    pub uses: Option<Uses>,
+    pub token_program_version: TokenProgramVersion,
+    pub creators: Vec<Creator>,
+}
+
+impl MetadataArgs {
+    pub fn data_hash(&self) -> Result<[u8; 32]> {
+        let metadata_args_hash = keccak::hashv(&[self.try_to_vec()?.as_slice()]);
+        Ok(keccak::hashv(&[
+            &metadata_args_hash.to_bytes(),
+            &self.seller_fee_basis_points.to_le_bytes(),
+        ])
+        .to_bytes())
+    }
+
+    pub fn creator_hash(&self) -> [u8; 32] {
+        let creator_data = self
+            .creators
+            .iter()
+            .map(|c| [c.address.as_ref(), &[c.verified as u8], &[c.share]].concat())
+            .collect::<Vec<_>>();
+        keccak::hashv(
+            creator_data
+                .iter()
+                .map(|c| c.as_slice())
+                .collect::<Vec<&[u8]>>()
+                .as_ref(),
+        )
+        .to_bytes()
+    }
+}
+
+pub fn asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &bubblegum_program_id(),
+    )
+    .0
+}
+
+/// Hash of a Bubblegum `LeafSchema::V1`
+pub fn leaf_hash(
+    merkle_tree: &Pubkey,
+    nonce: u64,
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    metadata: &MetadataArgs,
+) -> Result<[u8; 32]> {
+    Ok(keccak::hashv(&[
+        &[1],
+        asset_id(merkle_tree, nonce).as_ref(),
+        owner.as_ref(),
+        delegate.as_ref(),
+        &nonce.to_le_bytes(),
+        &metadata.data_hash()?,
+        &metadata.creator_hash(),
+    ])
+    .to_bytes())
+}
+
+// Offsets into a V1 concurrent merkle tree account
+const HEADER_SIZE: usize = 56;
+const MAX_DEPTH_OFFSET: usize = 6;
+const AUTHORITY_OFFSET: usize = 10;
+const ACTIVE_INDEX_OFFSET: usize = HEADER_SIZE + 8;
+const CHANGE_LOGS_OFFSET: usize = HEADER_SIZE + 24;
+
+/// The header and current root of a Bubblegum concurrent merkle tree
+pub struct MerkleTree {
+    pub max_depth: u32,
+    pub authority: Pubkey,
+    pub root: [u8; 32],
+}
+
+impl MerkleTree {
+    pub fn try_from_data(data: &[u8]) -> Option<Self> {
+        let u32_at = |offset: usize| {
+            data.get(offset..offset + 4)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u32::from_le_bytes)
+        };
+        let u64_at = |offset: usize| {
+            data.get(offset..offset + 8)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u64::from_le_bytes)
+        };
+
+        // Account type 1 is a concurrent merkle tree, header version 0 is V1
+        if data.get(..2)? != [1, 0] {
+            return None;
+        }
+        let max_depth = u32_at(MAX_DEPTH_OFFSET)?;
+        let authority = data
+            .get(AUTHORITY_OFFSET..AUTHORITY_OFFSET + 32)
+            .and_then(|bytes| Pubkey::try_from(bytes).ok())?;
+        let active_index = u64_at(ACTIVE_INDEX_OFFSET)? as usize;
+        // Each change log is a root, a path of max_depth nodes, an index and padding
+        let change_log_size = 32 + 32 * max_depth as usize + 4 + 4;
+        let root_offset = CHANGE_LOGS_OFFSET + active_index * change_log_size;
+        let root = data.get(root_offset..root_offset + 32)?.try_into().ok()?;
+
+        Some(Self {
+            max_depth,
+            authority,
+            root,
+        })
+    }
+
+    /// Whether the leaf at index is in the tree's current root. Trees with a canopy
+    /// are not supported, the full proof must be passed
+    pub fn verify(&self, leaf: [u8; 32], proof: &[[u8; 32]], index: u32) -> bool {
+        if proof.len() != self.max_depth as usize {
+            return false;
+        }
+
+        let root = proof
+            .iter()
+            .enumerate()
+            .fold(leaf, |node, (depth, sibling)| {
+                if (index >> depth) & 1 == 0 {
+                    keccak::hashv(&[&node, sibling]).to_bytes()
+                } else {
+                    keccak::hashv(&[sibling, &node]).to_bytes()
+                }
+            });
+
+        root == self.root
+    }
+}
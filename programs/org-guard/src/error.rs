@@ -8,10 +8,42 @@ pub enum ErrorCode {
     CollectionVerificationFailed,
     #[msg("The asset does not have enough weight to meet guard requirements")]
     InsufficientWeight,
-    #[msg("The first verified creator address does not meet guard requirements")]
+    #[msg("The first creator is either not verified or does not meet guard requirements")]
     FirstCreatorAddressVerificationFailed,
     #[msg("The proposer does not meet guard requirements")]
     ProposerNotValid,
     #[msg("The instruction is not allowed for this guard")]
     InstructionNotAllowed,
+    #[msg("Composite guards must not be empty, too deep or contain merkle wallet guards")]
+    InvalidCompositeGuard,
+    #[msg("An account passed as a guard proof is invalid")]
+    InvalidProof,
+    #[msg("The reputation config does not match the guard")]
+    InvalidReputationConfig,
+    #[msg("Programmable NFTs require an unlocked and undelegated token record")]
+    InvalidTokenRecord,
+    #[msg("The compressed NFT could not be verified against the merkle tree")]
+    CompressedNftVerificationFailed,
+    #[msg("No verified creator meets guard requirements")]
+    CreatorVerificationFailed,
+    #[msg("The proposal choices do not match the guard's template")]
+    ChoicesNotAllowed,
+    #[msg("A proposal tag is not allowed by the guard's template")]
+    TagNotAllowed,
+    #[msg("The proposal uri exceeds the guard's maximum length")]
+    UriTooLong,
+    #[msg("The proposal config is not allowed by the guard's template")]
+    ProposalConfigNotAllowed,
+    #[msg("valid_until must be after valid_from")]
+    InvalidGuardLimits,
+    #[msg("The guard is not active at this time")]
+    GuardNotActive,
+    #[msg("The proposer has reached the guard's proposal limit")]
+    ProposalLimitReached,
+    #[msg("Guards with a proposal limit require the proposer's guard usage account")]
+    InvalidGuardUsage,
+    #[msg("The wallet could not be verified against the guard's merkle root")]
+    WalletProofVerificationFailed,
+    #[msg("The voting escrow's lockup has ended")]
+    EscrowLockupExpired,
 }
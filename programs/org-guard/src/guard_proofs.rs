@@ -0,0 +1,238 @@
+use crate::error::ErrorCode;
+use crate::instructions::{nft_weight, reputation_weight, token_weight, wallet_weight};
+use crate::metaplex::{Key, MetadataAccount, TokenRecordAccount, TokenStandard, TokenState};
+use crate::state::*;
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
+use anchor_spl::{token, token_2022, token_interface::TokenAccount};
+use reputation::{ReputationConfigV0, ReputationManagerV0};
+
+/// Amount of a token account the proposer controls, either as owner or as delegate
+pub fn proposer_amount(token: &TokenAccount, proposer: &Pubkey) -> Option<u64> {
+    if token.owner == *proposer {
+        Some(token.amount)
+    } else if token.delegate == COption::Some(*proposer) {
+        Some(token.delegated_amount.min(token.amount))
+    } else {
+        None
+    }
+}
+
+pub struct TokenProof {
+    pub key: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Token, metadata, reputation and escrow accounts proving a proposer's weight for any guard type
+pub struct GuardProofs<'a, 'info> {
+    pub proposer: &'a AccountInfo<'info>,
+    pub tokens: Vec<TokenProof>,
+    pub metadatas: Vec<MetadataAccount>,
+    pub token_records: Vec<(Pubkey, TokenRecordAccount)>,
+    pub rep_configs: Vec<(Pubkey, ReputationConfigV0)>,
+    pub reputation_managers: Vec<ReputationManagerV0>,
+    /// Accounts owned by other programs, as (key, program, data), matched against escrow layouts
+    pub escrows: Vec<(Pubkey, Pubkey, Vec<u8>)>,
+}
+
+impl<'a, 'info> GuardProofs<'a, 'info> {
+    pub fn try_from_accounts(
+        proposer: &'a AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+    ) -> Result<Self> {
+        let mut tokens = vec![];
+        let mut metadatas = vec![];
+        let mut token_records = vec![];
+        let mut rep_configs = vec![];
+        let mut reputation_managers = vec![];
+        let mut escrows = vec![];
+
+        for (index, account) in accounts.iter().enumerate() {
+            // The same account passed twice would count its weight twice
+            require!(
+                accounts[..index].iter().all(|a| a.key != account.key),
+                ErrorCode::InvalidProof
+            );
+
+            if *account.owner == token::ID || *account.owner == token_2022::ID {
+                let token_account = TokenAccount::try_deserialize(&mut &account.data.borrow()[..])?;
+                let amount = proposer_amount(&token_account, proposer.key)
+                    .ok_or(ErrorCode::ProposerNotValid)?;
+                tokens.push(TokenProof {
+                    key: account.key(),
+                    mint: token_account.mint,
+                    amount,
+                });
+            } else if *account.owner == MetadataAccount::owner()
+                && account.data.borrow().first() == Some(&(Key::TokenRecord as u8))
+            {
+                let token_record =
+                    TokenRecordAccount::try_deserialize(&mut &account.data.borrow()[..])?;
+                token_records.push((account.key(), token_record));
+            } else if *account.owner == MetadataAccount::owner() {
+                let metadata = MetadataAccount::try_deserialize(&mut &account.data.borrow()[..])?;
+                let (expected, _) = Pubkey::find_program_address(
+                    &[
+                        b"metadata",
+                        MetadataAccount::owner().as_ref(),
+                        metadata.mint.as_ref(),
+                    ],
+                    &MetadataAccount::owner(),
+                );
+                require_keys_eq!(account.key(), expected, ErrorCode::InvalidProof);
+                metadatas.push(metadata);
+            } else if *account.owner == reputation::ID {
+                let data = account.data.borrow();
+                if let Ok(rep_config) = ReputationConfigV0::try_deserialize(&mut &data[..]) {
+                    rep_configs.push((account.key(), rep_config));
+                } else {
+                    let reputation_manager = ReputationManagerV0::try_deserialize(&mut &data[..])?;
+                    require_keys_eq!(
+                        reputation_manager.wallet,
+                        proposer.key(),
+                        ErrorCode::ProposerNotValid
+                    );
+                    require_keys_eq!(
+                        account.key(),
+                        ReputationManagerV0::address(
+                            &reputation_manager.repuation_config,
+                            &reputation_manager.wallet
+                        ),
+                        ErrorCode::InvalidProof
+                    );
+                    reputation_managers.push(reputation_manager);
+                }
+            } else {
+                escrows.push((
+                    account.key(),
+                    *account.owner,
+                    account.data.borrow().to_vec(),
+                ));
+            }
+        }
+
+        Ok(Self {
+            proposer,
+            tokens,
+            metadatas,
+            token_records,
+            rep_configs,
+            reputation_managers,
+            escrows,
+        })
+    }
+
+    /// Total weight of the proofs for a guard type. Leaf guards sum the weight of every
+    /// qualifying proof, `AnyOf` takes the highest passing branch and `AllOf` the lowest.
+    pub fn weight(&self, guard_type: &GuardType) -> Result<u64> {
+        match guard_type {
+            GuardType::AnyOf { guards } => guards
+                .iter()
+                .filter_map(|guard| self.weight(guard).ok())
+                .max()
+                .ok_or_else(|| ErrorCode::InsufficientWeight.into()),
+            GuardType::AllOf { guards } => {
+                guards.iter().try_fold(
+                    u64::MAX,
+                    |weight, guard| Ok(weight.min(self.weight(guard)?)),
+                )
+            }
+            GuardType::CollectionMint { .. }
+            | GuardType::FirstCreatorAddress { .. }
+            | GuardType::AnyVerifiedCreator { .. } => sum_qualifying(
+                self.tokens.iter().flat_map(|token| {
+                    self.metadatas
+                        .iter()
+                        .filter(move |metadata| metadata.mint == token.mint)
+                        .map(move |metadata| {
+                            self.assert_programmable(metadata, token)?;
+                            nft_weight(guard_type, metadata, token.amount)
+                        })
+                }),
+                ErrorCode::InsufficientWeight,
+            ),
+            GuardType::MintList { .. } => sum_qualifying(
+                self.tokens
+                    .iter()
+                    .map(|token| token_weight(guard_type, token.mint, token.amount)),
+                ErrorCode::MintNotValid,
+            ),
+            GuardType::VotingEscrow { layout, guard_data } => {
+                let now = Clock::get()?.unix_timestamp;
+                let mut weights: Vec<Result<u64>> = vec![];
+                // An escrow counts once, even when the proposer is both its owner and its delegate
+                let mut counted = vec![];
+                for (key, program_id, data) in &self.escrows {
+                    let escrow = match layout.read(program_id, data) {
+                        Some(escrow) => escrow,
+                        None => continue,
+                    };
+                    require!(
+                        escrow.owner == self.proposer.key()
+                            || escrow.delegate == Some(self.proposer.key()),
+                        ErrorCode::InvalidProof
+                    );
+                    require_gt!(escrow.lockup_end, now, ErrorCode::EscrowLockupExpired);
+                    if counted.contains(&key) {
+                        continue;
+                    }
+                    counted.push(key);
+
+                    weights.push(
+                        guard_data
+                            .iter()
+                            .find(|config| config.address == escrow.mint)
+                            .and_then(|config| escrow.amount.checked_div(config.divisor))
+                            .ok_or_else(|| ErrorCode::MintNotValid.into()),
+                    );
+                }
+
+                sum_qualifying(weights.into_iter(), ErrorCode::MintNotValid)
+            }
+            GuardType::WalletList { .. } => wallet_weight(guard_type, self.proposer),
+            // Merkle proofs are instruction args, only `initialize_proposal_by_merkle_wallet_v0`
+            // can verify them
+            GuardType::MerkleWallets { .. } => Err(ErrorCode::InstructionNotAllowed.into()),
+            GuardType::Reputation { .. } => sum_qualifying(
+                self.rep_configs.iter().flat_map(|(key, rep_config)| {
+                    self.reputation_managers
+                        .iter()
+                        .filter(move |manager| manager.repuation_config == *key)
+                        .map(move |manager| {
+                            reputation_weight(guard_type, *key, rep_config, manager)
+                        })
+                }),
+                ErrorCode::InsufficientWeight,
+            ),
+            GuardType::Permissive => Ok(u64::MAX),
+        }
+    }
+
+    /// Programmable NFTs only count alongside their token record, and not while locked,
+    /// listed or delegated, since a delegate could move or use them elsewhere
+    fn assert_programmable(&self, metadata: &MetadataAccount, token: &TokenProof) -> Result<()> {
+        if metadata.token_standard != Some(TokenStandard::ProgrammableNonFungible) {
+            return Ok(());
+        }
+
+        let address = TokenRecordAccount::address(&token.mint, &token.key);
+        let (_, token_record) = self
+            .token_records
+            .iter()
+            .find(|(key, _)| *key == address)
+            .ok_or(ErrorCode::InvalidTokenRecord)?;
+        require!(
+            token_record.state == TokenState::Unlocked && token_record.delegate.is_none(),
+            ErrorCode::InvalidTokenRecord
+        );
+
+        Ok(())
+    }
+}
+
+fn sum_qualifying(weights: impl Iterator<Item = Result<u64>>, error: ErrorCode) -> Result<u64> {
+    weights
+        .filter_map(|weight| weight.ok())
+        .reduce(|total, weight| total.saturating_add(weight))
+        .ok_or_else(|| error.into())
+}
@@ -0,0 +1,20 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseGuardV0<'info> {
+    /// CHECK: Receives the rent from closing the guard
+    #[account(mut)]
+    pub refund: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = refund,
+        has_one = authority,
+    )]
+    pub guard: Box<Account<'info, GuardV0>>,
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(_ctx: Context<CloseGuardV0>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeGuardUsageV0<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub guard: Box<Account<'info, GuardV0>>,
+    /// CHECK: The proposer whose usage is tracked
+    pub proposer: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + GuardUsageV0::INIT_SPACE,
+        seeds = [b"guard_usage", guard.key().as_ref(), proposer.key().as_ref()],
+        bump
+    )]
+    pub guard_usage: Box<Account<'info, GuardUsageV0>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeGuardUsageV0>) -> Result<()> {
+    ctx.accounts.guard_usage.set_inner(GuardUsageV0 {
+        guard: ctx.accounts.guard.key(),
+        proposer: ctx.accounts.proposer.key(),
+        num_proposals: 0,
+        bump: ctx.bumps["guard_usage"],
+    });
+
+    Ok(())
+}
@@ -5,6 +5,9 @@ use anchor_lang::prelude::*;
 pub struct InitializeGuardArgsV0 {
     pub name: String,
     pub guard_type: GuardType,
+    pub min_weight: u64,
+    pub template: Option<ProposalTemplate>,
+    pub limits: Option<GuardLimits>,
 }
 
 #[derive(Accounts)]
@@ -15,19 +18,34 @@ pub struct InitializeGuardV0<'info> {
     #[account(
         init,
         payer = payer,
-        space = 8 + GuardV0::space(&args.name, &args.guard_type),
-        seeds = [b"guard", args.name.as_bytes()],
+        space = GuardV0::space(
+            &args.name,
+            &args.guard_type,
+            args.template.as_ref().unwrap_or(&ProposalTemplate::default())
+        ),
+        seeds = [b"guard", authority.key().as_ref(), args.name.as_bytes()],
         bump
     )]
     pub nft_guard: Box<Account<'info, GuardV0>>,
+    /// Signs so that no one else can claim a guard address under this authority
+    pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<InitializeGuardV0>, args: InitializeGuardArgsV0) -> Result<()> {
+    args.guard_type.validate()?;
+    let limits = args.limits.unwrap_or_default();
+    limits.validate()?;
+
     ctx.accounts.nft_guard.set_inner(GuardV0 {
+        authority: ctx.accounts.authority.key(),
         name: args.name,
         guard_type: args.guard_type,
+        min_weight: args.min_weight,
+        template: args.template.unwrap_or_default(),
+        limits,
         bump: ctx.bumps["nft_guard"],
+        seed_authority: ctx.accounts.authority.key(),
     });
 
     Ok(())
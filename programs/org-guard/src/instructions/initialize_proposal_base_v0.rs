@@ -1,4 +1,5 @@
-use crate::state::*;
+use crate::error::ErrorCode;
+use crate::{guard_seeds, state::*};
 use anchor_lang::prelude::*;
 use organization::state::OrganizationV0;
 
@@ -26,6 +27,9 @@ pub struct InitializeProposalBaseV0<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     pub guard: Account<'info, GuardV0>,
+    /// The proposer's usage of the guard, required when the guard limits proposals per proposer
+    #[account(mut)]
+    pub guard_usage: Option<Box<Account<'info, GuardUsageV0>>>,
     /// CHECK: Setting this account, does not need a check. Putting here instead of args to save tx space
     pub owner: UncheckedAccount<'info>,
     #[account(
@@ -60,10 +64,42 @@ pub struct InitializeProposalBaseV0<'info> {
 }
 
 pub fn cpi_initialize_proposal<'info>(
-    accounts: &InitializeProposalBaseV0<'info>,
+    accounts: &mut InitializeProposalBaseV0<'info>,
+    proposer: &Pubkey,
     args: InitializeProposalArgsV0,
 ) -> Result<()> {
-    let bump = accounts.guard.bump;
+    accounts
+        .guard
+        .limits
+        .assert_active(Clock::get()?.unix_timestamp)?;
+    accounts
+        .guard
+        .template
+        .assert_proposal(&args, accounts.proposal_config.key)?;
+
+    let guard = accounts.guard.key();
+    match (
+        accounts.guard_usage.as_mut(),
+        accounts.guard.limits.max_proposals_per_proposer,
+    ) {
+        (Some(guard_usage), max_proposals) => {
+            require!(
+                guard_usage.guard == guard && guard_usage.proposer == *proposer,
+                ErrorCode::InvalidGuardUsage
+            );
+            if let Some(max_proposals) = max_proposals {
+                require_gt!(
+                    max_proposals,
+                    guard_usage.num_proposals,
+                    ErrorCode::ProposalLimitReached
+                );
+            }
+            guard_usage.num_proposals += 1;
+        }
+        (None, Some(_)) => return Err(ErrorCode::InvalidGuardUsage.into()),
+        (None, None) => {}
+    }
+
     let choices: Vec<organization::instructions::ChoiceArg> = args
         .choices
         .into_iter()
@@ -86,7 +122,7 @@ pub fn cpi_initialize_proposal<'info>(
                 proposal_program: accounts.proposal_program.to_account_info(),
                 system_program: accounts.system_program.to_account_info(),
             },
-            &[&[b"guard", accounts.guard.name.as_bytes(), &[bump]]],
+            &[guard_seeds!(accounts.guard)],
         ),
         organization::instructions::InitializeProposalArgsV0 {
             name: args.name,
@@ -0,0 +1,78 @@
+use crate::bubblegum::{self, MerkleTree, MetadataArgs};
+use crate::error::ErrorCode;
+use crate::initialize_proposal_base_v0::*;
+use crate::initialize_proposal_by_nft_v0::asset_weight;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CompressedNftProofArgsV0 {
+    pub nonce: u64,
+    pub index: u32,
+    pub delegate: Pubkey,
+    pub metadata: MetadataArgs,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProposalByCompressedNftV0<'info> {
+    pub initialize_proposal_base: InitializeProposalBaseV0<'info>,
+
+    pub proposer: Signer<'info>,
+    /// CHECK: Checked via owner and the tree authority being the bubblegum tree config
+    #[account(
+        owner = bubblegum::compression_program_id(),
+    )]
+    pub merkle_tree: UncheckedAccount<'info>,
+}
+
+/// The merkle proof nodes are passed as remaining accounts, as with bubblegum
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeProposalByCompressedNftV0<'info>>,
+    args: InitializeProposalArgsV0,
+    proof_args: CompressedNftProofArgsV0,
+) -> Result<()> {
+    let base = &ctx.accounts.initialize_proposal_base;
+    let merkle_tree_key = ctx.accounts.merkle_tree.key();
+
+    let merkle_tree = MerkleTree::try_from_data(&ctx.accounts.merkle_tree.data.borrow())
+        .ok_or(ErrorCode::CompressedNftVerificationFailed)?;
+    let (tree_config, _) = Pubkey::find_program_address(
+        &[merkle_tree_key.as_ref()],
+        &bubblegum::bubblegum_program_id(),
+    );
+    require_keys_eq!(
+        merkle_tree.authority,
+        tree_config,
+        ErrorCode::CompressedNftVerificationFailed
+    );
+
+    let leaf = bubblegum::leaf_hash(
+        &merkle_tree_key,
+        proof_args.nonce,
+        ctx.accounts.proposer.key,
+        &proof_args.delegate,
+        &proof_args.metadata,
+    )?;
+    let proof = ctx
+        .remaining_accounts
+        .iter()
+        .map(|node| node.key.to_bytes())
+        .collect::<Vec<_>>();
+    require!(
+        merkle_tree.verify(leaf, &proof, proof_args.index),
+        ErrorCode::CompressedNftVerificationFailed
+    );
+
+    let weight = asset_weight(
+        &base.guard.guard_type,
+        proof_args.metadata.collection.as_ref(),
+        Some(proof_args.metadata.creators.as_slice()),
+        1,
+    )?;
+    base.guard.assert_min_weight(weight)?;
+
+    cpi_initialize_proposal(
+        &mut ctx.accounts.initialize_proposal_base,
+        ctx.accounts.proposer.key,
+        args,
+    )
+}
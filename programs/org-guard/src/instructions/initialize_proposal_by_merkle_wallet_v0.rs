@@ -0,0 +1,58 @@
+use crate::error::ErrorCode;
+use crate::initialize_proposal_base_v0::*;
+use crate::merkle_wallets;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WalletProofArgsV0 {
+    pub multiplier: u16,
+    pub proof: Vec<[u8; 32]>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProposalByMerkleWalletV0<'info> {
+    pub initialize_proposal_base: InitializeProposalBaseV0<'info>,
+
+    proposer: Signer<'info>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeProposalByMerkleWalletV0<'info>>,
+    args: InitializeProposalArgsV0,
+    proof_args: WalletProofArgsV0,
+) -> Result<()> {
+    let base = &ctx.accounts.initialize_proposal_base;
+
+    let weight = merkle_wallet_weight(&base.guard.guard_type, &ctx.accounts.proposer, &proof_args)?;
+    base.guard.assert_min_weight(weight)?;
+
+    cpi_initialize_proposal(
+        &mut ctx.accounts.initialize_proposal_base,
+        ctx.accounts.proposer.key,
+        args,
+    )
+}
+
+/// `MerkleWallets` guards require a proof of the proposer's (wallet, multiplier) leaf
+pub fn merkle_wallet_weight(
+    guard_type: &GuardType,
+    proposer: &AccountInfo,
+    proof_args: &WalletProofArgsV0,
+) -> Result<u64> {
+    let root = match guard_type {
+        GuardType::MerkleWallets { root } => Ok(root),
+        _ => Err(ErrorCode::InstructionNotAllowed),
+    }?;
+
+    require!(
+        merkle_wallets::verify(
+            root,
+            merkle_wallets::leaf_hash(proposer.key, proof_args.multiplier),
+            &proof_args.proof,
+        ),
+        ErrorCode::WalletProofVerificationFailed
+    );
+
+    Ok(proof_args.multiplier as u64)
+}
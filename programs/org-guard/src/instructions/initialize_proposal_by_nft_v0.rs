@@ -1,6 +1,7 @@
 use crate::error::ErrorCode;
+use crate::guard_proofs::GuardProofs;
 use crate::initialize_proposal_base_v0::*;
-use crate::metaplex::MetadataAccount;
+use crate::metaplex::{Collection, Creator, MetadataAccount};
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_spl::token::TokenAccount;
@@ -26,30 +27,59 @@ pub struct InitializeProposalByNftV0<'info> {
     pub token_account: Box<Account<'info, TokenAccount>>,
 }
 
+/// Additional metadata and token account pairs may be passed as remaining accounts
+/// to add their weight to the proposer's. Programmable NFTs must also pass their token record
 pub fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, InitializeProposalByNftV0<'info>>,
     args: InitializeProposalArgsV0,
 ) -> Result<()> {
     let base = &ctx.accounts.initialize_proposal_base;
 
-    assert_sufficient_weight(
+    nft_weight(
         &base.guard.guard_type,
         &ctx.accounts.metadata,
-        &ctx.accounts.token_account,
+        ctx.accounts.token_account.amount,
     )?;
 
-    cpi_initialize_proposal(&base, args)
+    let proposer = ctx.accounts.proposer.to_account_info();
+    let accounts = [
+        ctx.accounts.metadata.to_account_info(),
+        ctx.accounts.token_account.to_account_info(),
+    ]
+    .into_iter()
+    .chain(ctx.remaining_accounts.iter().cloned())
+    .collect::<Vec<_>>();
+    let weight =
+        GuardProofs::try_from_accounts(&proposer, &accounts)?.weight(&base.guard.guard_type)?;
+
+    base.guard.assert_min_weight(weight)?;
+
+    cpi_initialize_proposal(
+        &mut ctx.accounts.initialize_proposal_base,
+        ctx.accounts.proposer.key,
+        args,
+    )
 }
 
-fn assert_sufficient_weight(
+pub fn nft_weight(guard_type: &GuardType, metadata: &MetadataAccount, amount: u64) -> Result<u64> {
+    asset_weight(
+        guard_type,
+        metadata.collection.as_ref(),
+        metadata.data.creators.as_deref(),
+        amount,
+    )
+}
+
+/// Weight of an asset with the given collection and creators, shared by
+/// uncompressed and compressed NFTs
+pub fn asset_weight(
     guard_type: &GuardType,
-    metadata: &MetadataAccount,
-    token: &TokenAccount,
-) -> Result<()> {
+    collection: Option<&Collection>,
+    creators: Option<&[Creator]>,
+    amount: u64,
+) -> Result<u64> {
     let config = match guard_type {
-        GuardType::CollectionMint { guard_data } => metadata
-            .collection
-            .as_ref()
+        GuardType::CollectionMint { guard_data } => collection
             .filter(|col| col.verified)
             .and_then(|col| {
                 guard_data
@@ -58,11 +88,9 @@ fn assert_sufficient_weight(
                     .cloned()
             })
             .ok_or(ErrorCode::CollectionVerificationFailed),
-        GuardType::FirstCreatorAddress { guard_data } => metadata
-            .data
-            .creators
-            .as_ref()
-            .and_then(|creators| creators.iter().find(|creator| creator.verified))
+        GuardType::FirstCreatorAddress { guard_data } => creators
+            .and_then(|creators| creators.first())
+            .filter(|first_creator| first_creator.verified)
             .and_then(|first_creator| {
                 guard_data
                     .iter()
@@ -70,12 +98,19 @@ fn assert_sufficient_weight(
                     .cloned()
             })
             .ok_or(ErrorCode::FirstCreatorAddressVerificationFailed),
+        GuardType::AnyVerifiedCreator { guard_data } => creators
+            .into_iter()
+            .flatten()
+            .filter(|creator| creator.verified)
+            .find_map(|creator| {
+                guard_data
+                    .iter()
+                    .find(|config| config.address == creator.address)
+                    .cloned()
+            })
+            .ok_or(ErrorCode::CreatorVerificationFailed),
         _ => Err(ErrorCode::InstructionNotAllowed.into()),
     }?;
 
-    if token.amount > 0 && config.multiplier > 0 {
-        Ok(())
-    } else {
-        Err(ErrorCode::InsufficientWeight.into())
-    }
+    Ok(amount.saturating_mul(config.multiplier as u64))
 }
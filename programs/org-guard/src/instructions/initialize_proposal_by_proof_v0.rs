@@ -0,0 +1,28 @@
+use crate::guard_proofs::GuardProofs;
+use crate::initialize_proposal_base_v0::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeProposalByProofV0<'info> {
+    pub initialize_proposal_base: InitializeProposalBaseV0<'info>,
+
+    pub proposer: Signer<'info>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeProposalByProofV0<'info>>,
+    args: InitializeProposalArgsV0,
+) -> Result<()> {
+    let base = &ctx.accounts.initialize_proposal_base;
+    let proposer = ctx.accounts.proposer.to_account_info();
+
+    let weight = GuardProofs::try_from_accounts(&proposer, ctx.remaining_accounts)?
+        .weight(&base.guard.guard_type)?;
+    base.guard.assert_min_weight(weight)?;
+
+    cpi_initialize_proposal(
+        &mut ctx.accounts.initialize_proposal_base,
+        ctx.accounts.proposer.key,
+        args,
+    )
+}
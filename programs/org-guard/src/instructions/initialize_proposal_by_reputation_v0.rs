@@ -0,0 +1,74 @@
+use crate::error::ErrorCode;
+use crate::initialize_proposal_base_v0::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use reputation::{ReputationConfigV0, ReputationManagerV0};
+
+#[derive(Accounts)]
+pub struct InitializeProposalByReputationV0<'info> {
+    pub initialize_proposal_base: InitializeProposalBaseV0<'info>,
+
+    pub proposer: Signer<'info>,
+    pub rep_config: Box<Account<'info, ReputationConfigV0>>,
+    #[account(
+        seeds = [b"reputation_manager", rep_config.key().as_ref(), proposer.key().as_ref()],
+        seeds::program = reputation::ID,
+        bump = reputation_manager.bump,
+        constraint = reputation_manager.wallet == proposer.key() @ ErrorCode::ProposerNotValid,
+        constraint = reputation_manager.repuation_config == rep_config.key() @ ErrorCode::InvalidReputationConfig,
+    )]
+    pub reputation_manager: Box<Account<'info, ReputationManagerV0>>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeProposalByReputationV0<'info>>,
+    args: InitializeProposalArgsV0,
+) -> Result<()> {
+    let base = &ctx.accounts.initialize_proposal_base;
+
+    let weight = reputation_weight(
+        &base.guard.guard_type,
+        ctx.accounts.rep_config.key(),
+        &ctx.accounts.rep_config,
+        &ctx.accounts.reputation_manager,
+    )?;
+    base.guard.assert_min_weight(weight)?;
+
+    cpi_initialize_proposal(
+        &mut ctx.accounts.initialize_proposal_base,
+        ctx.accounts.proposer.key,
+        args,
+    )
+}
+
+pub fn reputation_weight(
+    guard_type: &GuardType,
+    rep_config_key: Pubkey,
+    rep_config: &ReputationConfigV0,
+    reputation_manager: &ReputationManagerV0,
+) -> Result<u64> {
+    let min_score = match guard_type {
+        GuardType::Reputation {
+            rep_config,
+            min_score,
+        } => {
+            require_keys_eq!(
+                *rep_config,
+                rep_config_key,
+                ErrorCode::InvalidReputationConfig
+            );
+            Ok(*min_score)
+        }
+        _ => Err(ErrorCode::InstructionNotAllowed),
+    }?;
+
+    // The config's own gate applies on top of the guard's minimum
+    let min_score = min_score.max(rep_config.proposal_min_reputation_gate.unwrap_or(0));
+
+    let score = rep_config.score(&reputation_manager.reputation);
+    if score >= min_score {
+        Ok(score)
+    } else {
+        Err(ErrorCode::InsufficientWeight.into())
+    }
+}
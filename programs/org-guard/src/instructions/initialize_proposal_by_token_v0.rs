@@ -1,45 +1,66 @@
 use crate::error::ErrorCode;
+use crate::guard_proofs::{proposer_amount, GuardProofs};
 use crate::initialize_proposal_base_v0::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
+use anchor_spl::token_interface::TokenAccount;
 
 #[derive(Accounts)]
 pub struct InitializeProposalByTokenV0<'info> {
     pub initialize_proposal_base: InitializeProposalBaseV0<'info>,
 
     pub proposer: Signer<'info>,
+    /// SPL Token or Token-2022 account either owned by or delegated to the proposer
     #[account(
-        token::authority = proposer,
+        constraint = proposer_amount(&token_account, proposer.key).is_some() @ ErrorCode::ProposerNotValid,
     )]
-    pub token_account: Box<Account<'info, TokenAccount>>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 }
 
+/// Additional token accounts may be passed as remaining accounts to add their weight
+/// to the proposer's. Delegated token accounts only count their delegated amount
 pub fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, InitializeProposalByTokenV0<'info>>,
     args: InitializeProposalArgsV0,
 ) -> Result<()> {
     let base = &ctx.accounts.initialize_proposal_base;
 
-    assert_sufficient_weight(&base.guard.guard_type, &ctx.accounts.token_account)?;
+    let token_account = &ctx.accounts.token_account;
+    token_weight(
+        &base.guard.guard_type,
+        token_account.mint,
+        proposer_amount(token_account, ctx.accounts.proposer.key).unwrap_or(0),
+    )?;
 
-    cpi_initialize_proposal(&base, args)
+    let proposer = ctx.accounts.proposer.to_account_info();
+    let accounts = [ctx.accounts.token_account.to_account_info()]
+        .into_iter()
+        .chain(ctx.remaining_accounts.iter().cloned())
+        .collect::<Vec<_>>();
+    let weight =
+        GuardProofs::try_from_accounts(&proposer, &accounts)?.weight(&base.guard.guard_type)?;
+
+    base.guard.assert_min_weight(weight)?;
+
+    cpi_initialize_proposal(
+        &mut ctx.accounts.initialize_proposal_base,
+        ctx.accounts.proposer.key,
+        args,
+    )
 }
 
-fn assert_sufficient_weight(guard_type: &GuardType, token: &TokenAccount) -> Result<()> {
+pub fn token_weight(guard_type: &GuardType, mint: Pubkey, amount: u64) -> Result<u64> {
     let config = match guard_type {
         GuardType::MintList { guard_data } => guard_data
             .iter()
-            .find(|config| config.address == token.mint)
+            .find(|config| config.address == mint)
             .ok_or(ErrorCode::MintNotValid)
             .cloned(),
 
         _ => Err(ErrorCode::InstructionNotAllowed.into()),
     }?;
 
-    if token.amount >= config.divisor {
-        Ok(())
-    } else {
-        Err(ErrorCode::InsufficientWeight.into())
-    }
+    amount
+        .checked_div(config.divisor)
+        .ok_or_else(|| ErrorCode::InsufficientWeight.into())
 }
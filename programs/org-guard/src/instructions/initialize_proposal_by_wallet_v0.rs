@@ -16,12 +16,17 @@ pub fn handler<'info>(
 ) -> Result<()> {
     let base = &ctx.accounts.initialize_proposal_base;
 
-    assert_sufficient_weight(&base.guard.guard_type, &ctx.accounts.proposer)?;
+    let weight = wallet_weight(&base.guard.guard_type, &ctx.accounts.proposer)?;
+    base.guard.assert_min_weight(weight)?;
 
-    cpi_initialize_proposal(&base, args)
+    cpi_initialize_proposal(
+        &mut ctx.accounts.initialize_proposal_base,
+        ctx.accounts.proposer.key,
+        args,
+    )
 }
 
-fn assert_sufficient_weight(guard_type: &GuardType, proposer: &AccountInfo) -> Result<()> {
+pub fn wallet_weight(guard_type: &GuardType, proposer: &AccountInfo) -> Result<u64> {
     let config = match guard_type {
         GuardType::WalletList { guard_data } => guard_data
             .iter()
@@ -31,9 +36,5 @@ fn assert_sufficient_weight(guard_type: &GuardType, proposer: &AccountInfo) -> R
         _ => Err(ErrorCode::InstructionNotAllowed.into()),
     }?;
 
-    if config.multiplier > 0 {
-        Ok(())
-    } else {
-        Err(ErrorCode::InsufficientWeight.into())
-    }
+    Ok(config.multiplier as u64)
 }
@@ -12,10 +12,13 @@ pub fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, InitializeProposalPermissivelyV0<'info>>,
     args: InitializeProposalArgsV0,
 ) -> Result<()> {
-    let base = &ctx.accounts.initialize_proposal_base;
+    // Without a proposer, proposal limits apply to the payer
+    let payer = ctx.accounts.initialize_proposal_base.payer.key();
 
-    match base.guard.guard_type {
-        GuardType::Permissive => cpi_initialize_proposal(&base, args),
+    match ctx.accounts.initialize_proposal_base.guard.guard_type {
+        GuardType::Permissive => {
+            cpi_initialize_proposal(&mut ctx.accounts.initialize_proposal_base, &payer, args)
+        }
         _ => Err(ErrorCode::InstructionNotAllowed.into()),
     }
 }
@@ -1,13 +1,27 @@
+pub mod close_guard_v0;
+pub mod initialize_guard_usage_v0;
 pub mod initialize_guard_v0;
 pub mod initialize_proposal_base_v0;
+pub mod initialize_proposal_by_compressed_nft_v0;
+pub mod initialize_proposal_by_merkle_wallet_v0;
 pub mod initialize_proposal_by_nft_v0;
+pub mod initialize_proposal_by_proof_v0;
+pub mod initialize_proposal_by_reputation_v0;
 pub mod initialize_proposal_by_token_v0;
 pub mod initialize_proposal_by_wallet_v0;
 pub mod initialize_proposal_permissively_v0;
+pub mod update_guard_v0;
 
+pub use close_guard_v0::*;
+pub use initialize_guard_usage_v0::*;
 pub use initialize_guard_v0::*;
 pub use initialize_proposal_base_v0::*;
+pub use initialize_proposal_by_compressed_nft_v0::*;
+pub use initialize_proposal_by_merkle_wallet_v0::*;
 pub use initialize_proposal_by_nft_v0::*;
+pub use initialize_proposal_by_proof_v0::*;
+pub use initialize_proposal_by_reputation_v0::*;
 pub use initialize_proposal_by_token_v0::*;
 pub use initialize_proposal_by_wallet_v0::*;
 pub use initialize_proposal_permissively_v0::*;
+pub use update_guard_v0::*;
@@ -0,0 +1,57 @@
+use crate::state::*;
+use account_realloc::resize_to_fit;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdateGuardArgsV0 {
+    pub authority: Option<Pubkey>,
+    /// Replaces the guard type, allowing entries to be added or removed
+    pub guard_type: Option<GuardType>,
+    pub min_weight: Option<u64>,
+    /// Replaces the proposal template
+    pub template: Option<ProposalTemplate>,
+    /// Replaces the validity window and proposal limit
+    pub limits: Option<GuardLimits>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGuardV0<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        has_one = authority,
+    )]
+    pub guard: Box<Account<'info, GuardV0>>,
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<UpdateGuardV0>, args: UpdateGuardArgsV0) -> Result<()> {
+    if let Some(authority) = args.authority {
+        ctx.accounts.guard.authority = authority;
+    }
+    if let Some(guard_type) = args.guard_type {
+        guard_type.validate()?;
+        ctx.accounts.guard.guard_type = guard_type;
+    }
+    if let Some(min_weight) = args.min_weight {
+        ctx.accounts.guard.min_weight = min_weight;
+    }
+    if let Some(template) = args.template {
+        ctx.accounts.guard.template = template;
+    }
+    if let Some(limits) = args.limits {
+        limits.validate()?;
+        ctx.accounts.guard.limits = limits;
+    }
+
+    resize_to_fit(
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.guard,
+        anchor_lang::error::ErrorCode::AccountReallocExceedsLimit,
+    )?;
+
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
 
+pub mod bubblegum;
 pub mod error;
+pub mod guard_proofs;
 pub mod instructions;
+pub mod merkle_wallets;
 pub mod metaplex;
 pub mod state;
 
@@ -20,6 +23,26 @@ pub mod org_nft_guard {
         initialize_guard_v0::handler(ctx, args)
     }
 
+    pub fn initialize_guard_usage_v0(ctx: Context<InitializeGuardUsageV0>) -> Result<()> {
+        initialize_guard_usage_v0::handler(ctx)
+    }
+
+    pub fn update_guard_v0(ctx: Context<UpdateGuardV0>, args: UpdateGuardArgsV0) -> Result<()> {
+        update_guard_v0::handler(ctx, args)
+    }
+
+    pub fn close_guard_v0(ctx: Context<CloseGuardV0>) -> Result<()> {
+        close_guard_v0::handler(ctx)
+    }
+
+    pub fn initialize_proposal_by_compressed_nft_v0<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeProposalByCompressedNftV0<'info>>,
+        args: InitializeProposalArgsV0,
+        proof_args: CompressedNftProofArgsV0,
+    ) -> Result<()> {
+        initialize_proposal_by_compressed_nft_v0::handler(ctx, args, proof_args)
+    }
+
     pub fn initialize_proposal_by_nft_v0<'info>(
         ctx: Context<'_, '_, '_, 'info, InitializeProposalByNftV0<'info>>,
         args: InitializeProposalArgsV0,
@@ -41,6 +64,28 @@ pub mod org_nft_guard {
         initialize_proposal_by_wallet_v0::handler(ctx, args)
     }
 
+    pub fn initialize_proposal_by_merkle_wallet_v0<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeProposalByMerkleWalletV0<'info>>,
+        args: InitializeProposalArgsV0,
+        proof_args: WalletProofArgsV0,
+    ) -> Result<()> {
+        initialize_proposal_by_merkle_wallet_v0::handler(ctx, args, proof_args)
+    }
+
+    pub fn initialize_proposal_by_proof_v0<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeProposalByProofV0<'info>>,
+        args: InitializeProposalArgsV0,
+    ) -> Result<()> {
+        initialize_proposal_by_proof_v0::handler(ctx, args)
+    }
+
+    pub fn initialize_proposal_by_reputation_v0<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeProposalByReputationV0<'info>>,
+        args: InitializeProposalArgsV0,
+    ) -> Result<()> {
+        initialize_proposal_by_reputation_v0::handler(ctx, args)
+    }
+
     pub fn initialize_proposal_permissively_v0<'info>(
         ctx: Context<'_, '_, '_, 'info, InitializeProposalPermissivelyV0<'info>>,
         args: InitializeProposalArgsV0,
@@ -0,0 +1,76 @@
+use anchor_lang::{prelude::*, solana_program::keccak};
+
+/// Hash of a (wallet, multiplier) leaf of a `GuardType::MerkleWallets` tree
+pub fn leaf_hash(wallet: &Pubkey, multiplier: u16) -> [u8; 32] {
+    keccak::hashv(&[wallet.as_ref(), &multiplier.to_le_bytes()]).to_bytes()
+}
+
+/// Siblings are hashed in sorted order, so proofs do not need the leaf index
+fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        keccak::hashv(&[a, b]).to_bytes()
+    } else {
+        keccak::hashv(&[b, a]).to_bytes()
+    }
+}
+
+pub fn verify(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| node_hash(&node, sibling))
+        == *root
+}
+
+/// Builds a `GuardType::MerkleWallets` tree and its proofs off-chain. Host only, the program
+/// just verifies proofs
+#[cfg(not(target_os = "solana"))]
+pub struct WalletTree {
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl WalletTree {
+    pub fn new(entries: &[(Pubkey, u16)]) -> Self {
+        let mut layers = vec![entries
+            .iter()
+            .map(|(wallet, multiplier)| leaf_hash(wallet, *multiplier))
+            .collect::<Vec<_>>()];
+
+        while layers.last().unwrap().len() > 1 {
+            // An unpaired node moves up a layer unchanged
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node_hash(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Self { layers }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers
+            .last()
+            .and_then(|layer| layer.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Proof for the entry at `index`, in the order entries were passed to `new`
+    pub fn proof(&self, mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = vec![];
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
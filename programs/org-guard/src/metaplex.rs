@@ -173,3 +173,112 @@ pub struct Data {
     /// Array of creators, optional
     pub creators: Option<Vec<Creator>>,
 }
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TokenState {
+    /// Token account is unlocked; operations are allowed on this account.
+    Unlocked,
+    /// Token account has been locked; no operations are allowed on this account.
+    Locked,
+    /// Token account has a `Sale` delegate set; operations are restricted.
+    Listed,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TokenDelegateRole {
+    Sale,
+    Transfer,
+    Utility,
+    Staking,
+    Standard,
+    LockedTransfer,
+    Migration,
+}
+
+/// Per token account state of a programmable NFT.
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(Clone, BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct TokenRecord {
+    /// Account discriminator.
+    pub key: Key,
+    /// Bump of the PDA.
+    pub bump: u8,
+    /// State of the token account.
+    pub state: TokenState,
+    /// Revision of the rule set used for the last transfer.
+    pub rule_set_revision: Option<u64>,
+    /// Address of the delegate.
+    #[cfg_attr(
+        feature = "serde-feature",
+        serde(
+            deserialize_with = "deser_option_pubkey",
+            serialize_with = "ser_option_pubkey"
+        )
+    )]
+    pub delegate: Option<Pubkey>,
+    /// Role of the delegate.
+    pub delegate_role: Option<TokenDelegateRole>,
+    /// Address allowed to receive a locked transfer.
+    #[cfg_attr(
+        feature = "serde-feature",
+        serde(
+            deserialize_with = "deser_option_pubkey",
+            serialize_with = "ser_option_pubkey"
+        )
+    )]
+    pub locked_transfer: Option<Pubkey>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenRecordAccount(TokenRecord);
+
+impl TokenRecordAccount {
+    pub fn address(mint: &Pubkey, token: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"metadata",
+                MetadataAccount::owner().as_ref(),
+                mint.as_ref(),
+                b"token_record",
+                token.as_ref(),
+            ],
+            &MetadataAccount::owner(),
+        )
+        .0
+    }
+}
+
+impl anchor_lang::AccountDeserialize for TokenRecordAccount {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
+        let record = Self::try_deserialize_unchecked(buf)?;
+        if record.key != Key::TokenRecord {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Ok(record)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
+        let record = TokenRecord::deserialize(buf)?;
+        Ok(Self(record))
+    }
+}
+
+impl anchor_lang::AccountSerialize for TokenRecordAccount {}
+
+impl anchor_lang::Owner for TokenRecordAccount {
+    fn owner() -> Pubkey {
+        MetadataAccount::owner()
+    }
+}
+
+impl Deref for TokenRecordAccount {
+    type Target = TokenRecord;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
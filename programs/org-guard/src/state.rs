@@ -1,5 +1,10 @@
+use crate::error::ErrorCode;
+use crate::instructions::InitializeProposalArgsV0;
 use anchor_lang::prelude::*;
 
+/// Maximum nesting of composite guards, counting the leaf guards
+pub const MAX_GUARD_DEPTH: u8 = 3;
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub struct DivisorConfig {
     pub address: Pubkey,
@@ -12,13 +17,102 @@ pub struct MultiplierConfig {
     pub multiplier: u16,
 }
 
+/// Describes where a voting escrow program stores the owner, mint, locked amount and
+/// lockup end of an escrow account, so escrowed tokens can count towards a guard
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct EscrowLayout {
+    pub program_id: Pubkey,
+    pub discriminator: [u8; 8],
+    pub owner_offset: u16,
+    pub mint_offset: u16,
+    pub amount_offset: u16,
+    /// Offset of the unix timestamp (i64) at which the lockup ends
+    pub lockup_end_offset: u16,
+    /// Offset of an optional delegate, which may use the escrow in place of its owner
+    pub delegate_offset: Option<u16>,
+}
+
+pub struct EscrowAccount {
+    pub owner: Pubkey,
+    pub delegate: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub lockup_end: i64,
+}
+
+impl EscrowLayout {
+    /// Reads an escrow account, if it matches this layout
+    pub fn read(&self, program_id: &Pubkey, data: &[u8]) -> Option<EscrowAccount> {
+        if *program_id != self.program_id || data.get(..8)? != self.discriminator {
+            return None;
+        }
+        let pubkey_at = |offset: u16| {
+            data.get(offset as usize..offset as usize + 32)
+                .and_then(|bytes| Pubkey::try_from(bytes).ok())
+        };
+        let bytes_at = |offset: u16| -> Option<[u8; 8]> {
+            data.get(offset as usize..offset as usize + 8)
+                .and_then(|bytes| bytes.try_into().ok())
+        };
+        let delegate = match self.delegate_offset {
+            // An unset delegate is stored as the default pubkey
+            Some(offset) => Some(pubkey_at(offset)?).filter(|key| *key != Pubkey::default()),
+            None => None,
+        };
+
+        Some(EscrowAccount {
+            owner: pubkey_at(self.owner_offset)?,
+            delegate,
+            mint: pubkey_at(self.mint_offset)?,
+            amount: u64::from_le_bytes(bytes_at(self.amount_offset)?),
+            lockup_end: i64::from_le_bytes(bytes_at(self.lockup_end_offset)?),
+        })
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub enum GuardType {
-    CollectionMint { guard_data: Vec<MultiplierConfig> },
-    FirstCreatorAddress { guard_data: Vec<MultiplierConfig> },
-    MintList { guard_data: Vec<DivisorConfig> },
-    WalletList { guard_data: Vec<MultiplierConfig> },
+    CollectionMint {
+        guard_data: Vec<MultiplierConfig>,
+    },
+    FirstCreatorAddress {
+        guard_data: Vec<MultiplierConfig>,
+    },
+    MintList {
+        guard_data: Vec<DivisorConfig>,
+    },
+    WalletList {
+        guard_data: Vec<MultiplierConfig>,
+    },
     Permissive,
+    /// Passes if any of the inner guards pass
+    AnyOf {
+        guards: Vec<GuardType>,
+    },
+    /// Passes if all of the inner guards pass
+    AllOf {
+        guards: Vec<GuardType>,
+    },
+    /// Matches any verified creator, where `FirstCreatorAddress` only matches `creators[0]`.
+    /// New variants must be appended to keep existing guards deserializable
+    AnyVerifiedCreator {
+        guard_data: Vec<MultiplierConfig>,
+    },
+    /// Root of a tree of (wallet, multiplier) leaves, for lists too large to store inline.
+    /// Only verified by `initialize_proposal_by_merkle_wallet_v0`, so it can't be nested
+    MerkleWallets {
+        root: [u8; 32],
+    },
+    /// Contribution score of the proposer in a reputation config
+    Reputation {
+        rep_config: Pubkey,
+        min_score: u64,
+    },
+    /// Tokens locked in a voting escrow program described by `layout`
+    VotingEscrow {
+        layout: EscrowLayout,
+        guard_data: Vec<DivisorConfig>,
+    },
 }
 
 impl GuardType {
@@ -26,26 +120,203 @@ impl GuardType {
         match self {
             GuardType::CollectionMint { guard_data }
             | GuardType::FirstCreatorAddress { guard_data }
-            | GuardType::WalletList { guard_data } => {
+            | GuardType::WalletList { guard_data }
+            | GuardType::AnyVerifiedCreator { guard_data } => {
                 1 + 4 + guard_data.len() * MultiplierConfig::INIT_SPACE
             }
             GuardType::MintList { guard_data } => {
                 1 + 4 + guard_data.len() * DivisorConfig::INIT_SPACE
             }
             GuardType::Permissive => 1,
+            GuardType::Reputation { .. } => 1 + 32 + 8,
+            GuardType::MerkleWallets { .. } => 1 + 32,
+            GuardType::VotingEscrow { guard_data, .. } => {
+                1 + EscrowLayout::INIT_SPACE + 4 + guard_data.len() * DivisorConfig::INIT_SPACE
+            }
+            GuardType::AnyOf { guards } | GuardType::AllOf { guards } => {
+                1 + 4 + guards.iter().map(|guard| guard.space()).sum::<usize>()
+            }
         }
     }
+
+    pub fn validate(&self) -> Result<()> {
+        self.validate_depth(1)
+    }
+
+    fn validate_depth(&self, depth: u8) -> Result<()> {
+        require_gte!(MAX_GUARD_DEPTH, depth, ErrorCode::InvalidCompositeGuard);
+
+        match self {
+            GuardType::AnyOf { guards } | GuardType::AllOf { guards } => {
+                require!(!guards.is_empty(), ErrorCode::InvalidCompositeGuard);
+                guards
+                    .iter()
+                    .try_for_each(|guard| guard.validate_depth(depth + 1))
+            }
+            // Composite guards are evaluated from proof accounts, which can't carry a merkle proof
+            GuardType::MerkleWallets { .. } => {
+                require_eq!(depth, 1, ErrorCode::InvalidCompositeGuard);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Constraints on the proposals a guard will sign for. Unset fields are not constrained
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ProposalTemplate {
+    /// Choice names proposals must use, in order (e.g. Yes/No/Abstain)
+    pub choices: Option<Vec<String>>,
+    pub max_choices_per_voter: Option<u16>,
+    /// Tags proposals may use
+    pub allowed_tags: Option<Vec<String>>,
+    pub max_uri_len: Option<u16>,
+    /// Proposal config proposals must use, rather than one chosen by the proposer
+    pub proposal_config: Option<Pubkey>,
+}
+
+impl ProposalTemplate {
+    pub fn space(&self) -> usize {
+        let strings_space = |strings: &Option<Vec<String>>| match strings {
+            Some(strings) => 1 + 4 + strings.iter().map(|s| 4 + s.len()).sum::<usize>(),
+            None => 1,
+        };
+
+        strings_space(&self.choices) + 3 + strings_space(&self.allowed_tags) + 3 + 33
+    }
+
+    pub fn assert_proposal(
+        &self,
+        args: &InitializeProposalArgsV0,
+        proposal_config: &Pubkey,
+    ) -> Result<()> {
+        if let Some(choices) = &self.choices {
+            require!(
+                args.choices.len() == choices.len()
+                    && args
+                        .choices
+                        .iter()
+                        .zip(choices)
+                        .all(|(choice, name)| choice.name == *name),
+                ErrorCode::ChoicesNotAllowed
+            );
+        }
+        if let Some(max_choices_per_voter) = self.max_choices_per_voter {
+            require_gte!(
+                max_choices_per_voter,
+                args.max_choices_per_voter,
+                ErrorCode::ChoicesNotAllowed
+            );
+        }
+        if let Some(allowed_tags) = &self.allowed_tags {
+            require!(
+                args.tags.iter().all(|tag| allowed_tags.contains(tag)),
+                ErrorCode::TagNotAllowed
+            );
+        }
+        if let Some(max_uri_len) = self.max_uri_len {
+            require_gte!(max_uri_len as usize, args.uri.len(), ErrorCode::UriTooLong);
+        }
+        if let Some(expected) = self.proposal_config {
+            require_keys_eq!(
+                *proposal_config,
+                expected,
+                ErrorCode::ProposalConfigNotAllowed
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// When and how often a guard will sign for proposals. Unset fields are not limited
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct GuardLimits {
+    pub valid_from: Option<i64>,
+    pub valid_until: Option<i64>,
+    /// Proposals each proposer may create, tracked in their `GuardUsageV0`
+    pub max_proposals_per_proposer: Option<u32>,
+}
+
+impl GuardLimits {
+    pub fn validate(&self) -> Result<()> {
+        if let (Some(valid_from), Some(valid_until)) = (self.valid_from, self.valid_until) {
+            require_gt!(valid_until, valid_from, ErrorCode::InvalidGuardLimits);
+        }
+        Ok(())
+    }
+
+    pub fn assert_active(&self, now: i64) -> Result<()> {
+        require!(
+            self.valid_from.map_or(true, |valid_from| now >= valid_from)
+                && self
+                    .valid_until
+                    .map_or(true, |valid_until| now < valid_until),
+            ErrorCode::GuardNotActive
+        );
+        Ok(())
+    }
 }
 
 #[account]
 pub struct GuardV0 {
+    /// Authority allowed to update or close this guard
+    pub authority: Pubkey,
     pub name: String,
     pub guard_type: GuardType,
+    /// Minimum weight a proposer must prove. Multiplier configs weigh NFT count × multiplier,
+    /// divisor configs weigh token amount / divisor. A weight of at least 1 is always required
+    pub min_weight: u64,
+    pub template: ProposalTemplate,
+    pub limits: GuardLimits,
     pub bump: u8,
+    /// Authority the guard address was derived from. Unlike `authority` it never changes,
+    /// so the guard can keep signing after its authority is transferred
+    pub seed_authority: Pubkey,
 }
 
 impl GuardV0 {
-    pub fn space(name: &String, guard_type: &GuardType) -> usize {
-        8 + name.len() + guard_type.space() + 1
+    pub fn space(name: &String, guard_type: &GuardType, template: &ProposalTemplate) -> usize {
+        8 + 32
+            + 4
+            + name.len()
+            + guard_type.space()
+            + 8
+            + template.space()
+            + GuardLimits::INIT_SPACE
+            + 1
+            + 32
+    }
+
+    pub fn assert_min_weight(&self, weight: u64) -> Result<()> {
+        require_gte!(
+            weight,
+            self.min_weight.max(1),
+            ErrorCode::InsufficientWeight
+        );
+        Ok(())
     }
 }
+
+/// Number of proposals a proposer has created through a guard
+#[account]
+#[derive(InitSpace)]
+pub struct GuardUsageV0 {
+    pub guard: Pubkey,
+    pub proposer: Pubkey,
+    pub num_proposals: u32,
+    pub bump: u8,
+}
+
+#[macro_export]
+macro_rules! guard_seeds {
+    ( $guard:expr ) => {
+        &[
+            b"guard",
+            $guard.seed_authority.as_ref(),
+            $guard.name.as_bytes(),
+            &[$guard.bump],
+        ]
+    };
+}
@@ -17,4 +17,36 @@ pub enum ErrorCode {
     InvalidProposalState,
     InvalidOrganization,
     InvalidProposalConfig,
+    #[msg("The transaction exceeds the wallet's spending limits")]
+    SpendingLimitExceeded,
+    #[msg("The transaction invokes a program not allowed by the wallet")]
+    ProgramNotAllowed,
+    #[msg("The transaction can still be executed")]
+    TransactionNotExpired,
+    #[msg("Earlier transactions of this choice must be executed first")]
+    TransactionOutOfOrder,
+    #[msg("Lookup table accounts do not match the transaction")]
+    InvalidLookupTable,
+    #[msg("The transaction data has already been finalized")]
+    TransactionFinalized,
+    #[msg("The transaction data has not been finalized")]
+    TransactionNotFinalized,
+    #[msg("The execution window must open before it closes")]
+    InvalidExecutionWindow,
+    #[msg("Transactions can only be vetoed after resolution, before their execution window opens")]
+    VetoWindowClosed,
+    #[msg("Pass either no token accounts for lamports, or the mint, source, destination and token program")]
+    InvalidDeposit,
+    #[msg("The proposal config's policy does not allow this transaction")]
+    PolicyViolation,
+    #[msg("The transaction changed the owner, delegate or close authority of a wallet token account")]
+    TokenAuthorityChanged,
+    #[msg("Lookup tables must be active when the transaction is set")]
+    LookupTableDeactivated,
+    #[msg("The transaction was vetoed by the guardian")]
+    TransactionVetoed,
+    #[msg("The transaction assigned the wallet to another program or allocated its data")]
+    WalletOwnerChanged,
+    #[msg("The account is not a choice transaction in the previous layout")]
+    NotLegacyTransaction,
 }
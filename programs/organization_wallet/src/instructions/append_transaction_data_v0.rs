@@ -0,0 +1,55 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use account_realloc::resize_to_fit;
+use anchor_lang::prelude::*;
+use proposal::{ProposalState, ProposalV0};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AppendTransactionDataArgsV0 {
+    pub instruction_index: u8,
+    pub data: Vec<u8>,
+}
+
+#[derive(Accounts)]
+pub struct AppendTransactionDataV0<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub owner: Signer<'info>,
+    #[account(
+    has_one = owner,
+    constraint = proposal.state == ProposalState::Draft @ ErrorCode::InvalidProposalState,
+  )]
+    pub proposal: Box<Account<'info, ProposalV0>>,
+    #[account(
+    mut,
+    has_one = proposal,
+    constraint = !choice_transaction.finalized @ ErrorCode::TransactionFinalized,
+  )]
+    pub choice_transaction: Box<Account<'info, ChoiceTransactionV0>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Appends a chunk to the data of an instruction of a chunked transaction, for payloads
+/// too large to pass to `set_transactions_v0` at once
+pub fn handler(
+    ctx: Context<AppendTransactionDataV0>,
+    args: AppendTransactionDataArgsV0,
+) -> Result<()> {
+    ctx.accounts
+        .choice_transaction
+        .transaction
+        .instructions
+        .get_mut(args.instruction_index as usize)
+        .ok_or(ErrorCode::InvalidTransactionIndex)?
+        .data
+        .extend(args.data);
+
+    resize_to_fit(
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.choice_transaction,
+        ErrorCode::InvalidDataIncrease,
+    )?;
+
+    Ok(())
+}
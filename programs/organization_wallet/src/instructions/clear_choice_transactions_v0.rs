@@ -0,0 +1,77 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use proposal::{ProposalState, ProposalV0};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ClearChoiceTransactionsArgsV0 {
+    pub choice_index: u16,
+}
+
+#[derive(Accounts)]
+pub struct ClearChoiceTransactionsV0<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+    has_one = owner,
+    constraint = proposal.state == ProposalState::Draft @ ErrorCode::InvalidProposalState,
+  )]
+    pub proposal: Box<Account<'info, ProposalV0>>,
+    #[account(
+    mut,
+    has_one = proposal,
+  )]
+    pub wallet_proposal: Box<Account<'info, WalletProposalV0>>,
+}
+
+/// Every choice transaction of the choice is passed as a writable remaining account, in any order,
+/// followed by the writable payers of those transactions, which receive back the rent they paid
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClearChoiceTransactionsV0<'info>>,
+    args: ClearChoiceTransactionsArgsV0,
+) -> Result<()> {
+    let wallet_proposal_key = ctx.accounts.wallet_proposal.key();
+    let num_transactions = ctx
+        .accounts
+        .wallet_proposal
+        .num_transactions_by_choice
+        .get(args.choice_index as usize)
+        .copied()
+        .unwrap_or(0) as usize;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        num_transactions,
+        ErrorCode::InvalidTransactionIndex
+    );
+    let (choice_transactions, payers) = ctx.remaining_accounts.split_at(num_transactions);
+
+    // Duplicate accounts would fail to deserialize after being closed
+    for account in choice_transactions {
+        let choice_transaction = Account::<ChoiceTransactionV0>::try_from(account)?;
+        require_keys_eq!(
+            choice_transaction.wallet_proposal,
+            wallet_proposal_key,
+            ErrorCode::InvalidAccount
+        );
+        require_eq!(
+            choice_transaction.choice_index,
+            args.choice_index,
+            ErrorCode::InvalidAccount
+        );
+        let payer = payers
+            .iter()
+            .find(|payer| payer.key() == choice_transaction.payer)
+            .ok_or(ErrorCode::InvalidAccount)?;
+        choice_transaction.close(payer.clone())?;
+    }
+
+    if let Some(num_transactions) = ctx
+        .accounts
+        .wallet_proposal
+        .num_transactions_by_choice
+        .get_mut(args.choice_index as usize)
+    {
+        *num_transactions = 0;
+    }
+
+    Ok(())
+}
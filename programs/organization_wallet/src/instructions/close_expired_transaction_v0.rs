@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use proposal::ProposalV0;
+
+#[derive(Accounts)]
+pub struct CloseExpiredTransactionV0<'info> {
+    /// CHECK: Checked via has one, receives the rent back
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    pub proposal: Box<Account<'info, ProposalV0>>,
+    #[account(
+    mut,
+    close = payer,
+    has_one = payer,
+    has_one = proposal,
+    constraint = choice_transaction.is_expired(&proposal.state, Clock::get()?.unix_timestamp) @ ErrorCode::TransactionNotExpired,
+  )]
+    pub choice_transaction: Box<Account<'info, ChoiceTransactionV0>>,
+    /// CHECK: Must not exist, executed transactions stay open to be audited against their receipt
+    #[account(
+    seeds = [b"execution_receipt", choice_transaction.key().as_ref()],
+    bump,
+    constraint = execution_receipt.data_is_empty() @ ErrorCode::TransactionAlreadyExecuted,
+  )]
+    pub execution_receipt: UncheckedAccount<'info>,
+}
+
+/// Permissionless, so rent is not locked in transactions for losing choices or past their window
+pub fn handler(_ctx: Context<CloseExpiredTransactionV0>) -> Result<()> {
+    Ok(())
+}
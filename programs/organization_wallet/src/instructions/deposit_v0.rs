@@ -0,0 +1,97 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct DepositArgsV0 {
+    pub amount: u64,
+}
+
+#[event]
+pub struct DepositEventV0 {
+    pub organization_wallet: Pubkey,
+    pub depositor: Pubkey,
+    /// `Pubkey::default()` for lamports
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Deposits lamports into the wallet, or tokens when the token accounts are passed
+#[derive(Accounts)]
+pub struct DepositV0<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    #[account(
+    has_one = wallet
+  )]
+    pub organization_wallet: Box<Account<'info, OrganizationWalletV0>>,
+    /// CHECK: Checked via has one
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut)]
+    pub source: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// Token account of the wallet for `mint`
+    #[account(mut)]
+    pub destination: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<DepositV0>, args: DepositArgsV0) -> Result<()> {
+    let mint = match (
+        &ctx.accounts.mint,
+        &ctx.accounts.source,
+        &ctx.accounts.destination,
+        &ctx.accounts.token_program,
+    ) {
+        (None, None, None, _) => {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.depositor.to_account_info(),
+                        to: ctx.accounts.wallet.to_account_info(),
+                    },
+                ),
+                args.amount,
+            )?;
+
+            Pubkey::default()
+        }
+        (Some(mint), Some(source), Some(destination), Some(token_program)) => {
+            require_keys_eq!(
+                destination.owner,
+                ctx.accounts.wallet.key(),
+                ErrorCode::InvalidDeposit
+            );
+            require_keys_eq!(destination.mint, mint.key(), ErrorCode::InvalidDeposit);
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    TransferChecked {
+                        from: source.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: destination.to_account_info(),
+                        authority: ctx.accounts.depositor.to_account_info(),
+                    },
+                ),
+                args.amount,
+                mint.decimals,
+            )?;
+
+            mint.key()
+        }
+        _ => return Err(error!(ErrorCode::InvalidDeposit)),
+    };
+
+    emit!(DepositEventV0 {
+        organization_wallet: ctx.accounts.organization_wallet.key(),
+        depositor: ctx.accounts.depositor.key(),
+        mint,
+        amount: args.amount,
+    });
+
+    Ok(())
+}
@@ -1,12 +1,31 @@
 use crate::error::ErrorCode;
+use crate::lookup_table::resolve_lookups;
 use crate::{state::*, wallet_seeds};
+use account_realloc::resize_to_fit;
 use anchor_lang::solana_program::instruction::Instruction;
-use anchor_lang::{prelude::*, solana_program};
+use anchor_lang::{
+    prelude::*, solana_program, solana_program::program_option::COption, system_program,
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TransferChecked};
+use anchor_spl::{token, token_2022};
 use proposal::{ProposalState, ProposalV0};
 
+/// Funds sent out of the wallet by a `TransferV0` action. Along with `DepositEventV0`,
+/// lets indexers account for typed flows, balances themselves are not tracked on chain
+#[event]
+pub struct WithdrawEventV0 {
+    pub organization_wallet: Pubkey,
+    pub choice_transaction: Pubkey,
+    /// `Pubkey::default()` for lamports
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
 #[derive(Accounts)]
 pub struct ExecuteTransactionV0<'info> {
     #[account(
+    mut,
     has_one = wallet
   )]
     pub organization_wallet: Box<Account<'info, OrganizationWalletV0>>,
@@ -18,31 +37,97 @@ pub struct ExecuteTransactionV0<'info> {
   )]
     pub proposal: Box<Account<'info, ProposalV0>>,
     #[account(
-    mut,
-    close = refund,
     has_one = proposal,
     has_one = organization_wallet,
+    constraint = choice_transaction.finalized @ ErrorCode::TransactionNotFinalized,
+    constraint = !choice_transaction.vetoed @ ErrorCode::TransactionVetoed,
+    has_one = wallet_proposal,
     constraint = match &proposal.state {
       ProposalState::Resolved { end_ts, .. } => {
-        let curr_ts = Clock::get()?.unix_timestamp;
-        let allow_exec_ts = choice_transaction.allow_execution_offset as i64 + end_ts;
-        let disable_exec_ts = choice_transaction.disable_execution_offset as i64 + end_ts;
-        curr_ts >= allow_exec_ts && curr_ts < disable_exec_ts
+        choice_transaction.execution_window.is_open(*end_ts, Clock::get()?.unix_timestamp)
       }
       _ => false,
     },
   )]
     pub choice_transaction: Box<Account<'info, ChoiceTransactionV0>>,
+    #[account(mut)]
+    pub wallet_proposal: Box<Account<'info, WalletProposalV0>>,
     /// CHECK: Checked via has one
     #[account(mut)]
     pub wallet: UncheckedAccount<'info>,
-    /// CHECK: Receivs the funds from closing choice transaction
+    /// Record of the execution. Choice transactions stay open so they can be audited
+    /// against the receipt's hash
+    #[account(
+    init_if_needed,
+    payer = payer,
+    space = 8 + ExecutionReceiptV0::INIT_SPACE,
+    seeds = [b"execution_receipt", choice_transaction.key().as_ref()],
+    bump,
+    constraint = execution_receipt.choice_transaction == Pubkey::default() @ ErrorCode::TransactionAlreadyExecuted,
+  )]
+    pub execution_receipt: Box<Account<'info, ExecutionReceiptV0>>,
     #[account(mut)]
-    pub refund: UncheckedAccount<'info>,
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<ExecuteTransactionV0>) -> Result<()> {
+    let choice_index = ctx.accounts.choice_transaction.choice_index as usize;
+    let transaction_index = ctx.accounts.choice_transaction.transaction_index;
+    let wallet_proposal = &mut ctx.accounts.wallet_proposal;
+    if wallet_proposal.executed_count_by_choice.len() <= choice_index {
+        wallet_proposal
+            .executed_count_by_choice
+            .resize(choice_index + 1, 0);
+    }
+    let ordered = wallet_proposal.ordered;
+    let vetoed = wallet_proposal
+        .vetoed_by_choice
+        .get(choice_index)
+        .cloned()
+        .unwrap_or_default();
+    let executed_count = &mut wallet_proposal.executed_count_by_choice[choice_index];
+    if ordered {
+        // Vetoed transactions will never execute, so they do not hold back later ones
+        while vetoed.contains(executed_count) {
+            *executed_count += 1;
+        }
+        require_eq!(
+            transaction_index,
+            *executed_count,
+            ErrorCode::TransactionOutOfOrder
+        );
+    }
+    *executed_count += 1;
+
+    ctx.accounts
+        .execution_receipt
+        .set_inner(ExecutionReceiptV0 {
+            choice_transaction: ctx.accounts.choice_transaction.key(),
+            proposal: ctx.accounts.proposal.key(),
+            executor: ctx.accounts.payer.key(),
+            executed_at: Clock::get()?.unix_timestamp,
+            transaction_hash: ctx.accounts.choice_transaction.transaction_hash,
+            choice_index: ctx.accounts.choice_transaction.choice_index,
+            transaction_index,
+            bump_seed: ctx.bumps["execution_receipt"],
+        });
+
     let transaction = &ctx.accounts.choice_transaction.transaction;
+    // The allowlist may have changed since the transaction was set
+    ctx.accounts
+        .organization_wallet
+        .assert_programs_allowed(transaction)?;
+    let policy = ctx
+        .accounts
+        .organization_wallet
+        .policy(&ctx.accounts.proposal.proposal_config)
+        .cloned();
+    if let (Some(policy), ProposalState::Resolved { end_ts, .. }) =
+        (&policy, &ctx.accounts.proposal.state)
+    {
+        policy.assert_can_execute(*end_ts, Clock::get()?.unix_timestamp)?;
+    }
     let wallet_seeds: &[&[u8]] = wallet_seeds!(ctx.accounts.organization_wallet);
 
     let prefix: Vec<&[u8]> = vec![
@@ -76,8 +161,32 @@ pub fn handler(ctx: Context<ExecuteTransactionV0>) -> Result<()> {
         .map(|s| Pubkey::create_program_address(s, ctx.program_id).unwrap())
         .collect::<std::collections::HashSet<Pubkey>>();
 
+    // Lookup tables are passed after the accounts of the transaction
+    let num_accounts = ctx
+        .remaining_accounts
+        .len()
+        .checked_sub(transaction.address_table_lookups.len())
+        .ok_or(ErrorCode::InvalidLookupTable)?;
+    let (accounts, tables) = ctx.remaining_accounts.split_at(num_accounts);
+    let (loaded_writable, loaded_readonly) =
+        resolve_lookups(&transaction.address_table_lookups, tables)?;
+    // The tables must still hold the addresses voters approved
+    require!(
+        loaded_writable == transaction.loaded_writable
+            && loaded_readonly == transaction.loaded_readonly,
+        ErrorCode::InvalidLookupTable
+    );
+    let num_static = transaction.accounts.len();
+    let keys = transaction
+        .accounts
+        .iter()
+        .chain(transaction.loaded_writable.iter())
+        .chain(transaction.loaded_readonly.iter())
+        .collect::<Vec<_>>();
+    require_eq!(accounts.len(), keys.len(), ErrorCode::InvalidAccount);
+
     // Validate txn
-    for (index, account) in ctx.remaining_accounts.iter().enumerate() {
+    for (index, account) in accounts.iter().enumerate() {
         let signers_end = (transaction.num_ro_signers + transaction.num_rw_signers) as usize;
         // It is okay if an account not labeled as a signer is a signer.
         // For example, if an account being passed is a fee payer
@@ -89,7 +198,8 @@ pub fn handler(ctx: Context<ExecuteTransactionV0>) -> Result<()> {
         }
 
         let is_writable = index < transaction.num_rw as usize
-            || (index >= signers_end && index < (signers_end + transaction.num_rw as usize));
+            || (index >= signers_end && index < (signers_end + transaction.num_rw as usize))
+            || (index >= num_static && index < num_static + transaction.loaded_writable.len());
         // While it would be nice to validate non-writable accounts aren't writable,
         // this is not possible. We can't tell who the tx fee payer is, so they may be writable
         // because of that. Or they may be the refund target.
@@ -97,19 +207,29 @@ pub fn handler(ctx: Context<ExecuteTransactionV0>) -> Result<()> {
             require!(account.is_writable, ErrorCode::InvalidWritable);
         }
 
-        require_eq!(
-            *account.key,
-            transaction.accounts[index],
-            ErrorCode::InvalidAccount
-        );
+        require_eq!(*account.key, *keys[index], ErrorCode::InvalidAccount);
     }
+    let has_limits = !ctx.accounts.organization_wallet.spending_limits.is_empty()
+        || policy
+            .as_ref()
+            .map_or(false, |policy| !policy.max_token_amounts.is_empty());
+    let token_accounts_before = if has_limits {
+        wallet_token_accounts(&ctx.accounts.wallet, accounts)
+    } else {
+        vec![]
+    };
+    let lamports_before = ctx.accounts.wallet.lamports();
+
     for ix in &transaction.instructions {
-        let mut accounts = Vec::new();
-        let mut account_infos = Vec::new();
+        let mut ix_accounts = Vec::new();
+        let mut account_metas = Vec::new();
         for i in &ix.accounts {
-            let acct = ctx.remaining_accounts[*i as usize].clone();
-            accounts.push(acct.clone());
-            account_infos.push(AccountMeta {
+            let acct = accounts
+                .get(*i as usize)
+                .ok_or(ErrorCode::InvalidAccount)?
+                .clone();
+            ix_accounts.push(acct.clone());
+            account_metas.push(AccountMeta {
                 pubkey: acct.key(),
                 is_signer: acct.key() == ctx.accounts.wallet.key()
                     || acct.is_signer
@@ -117,16 +237,237 @@ pub fn handler(ctx: Context<ExecuteTransactionV0>) -> Result<()> {
                 is_writable: acct.is_writable,
             })
         }
+        let program = accounts
+            .get(ix.program_id_index as usize)
+            .ok_or(ErrorCode::InvalidAccount)?;
         solana_program::program::invoke_signed(
             &Instruction {
-                program_id: *ctx.remaining_accounts[ix.program_id_index as usize].key,
-                accounts: account_infos,
+                program_id: *program.key,
+                accounts: account_metas,
                 data: ix.data.clone(),
             },
-            accounts.as_slice(),
+            ix_accounts.as_slice(),
             &signers,
         )?;
     }
 
+    for action in &transaction.actions {
+        execute_action(action, &ctx.accounts.wallet, accounts, wallet_seeds)?;
+        match *action {
+            ActionV0::TransferV0 {
+                mint,
+                amount,
+                destination_index,
+                ..
+            } => emit!(WithdrawEventV0 {
+                organization_wallet: ctx.accounts.organization_wallet.key(),
+                choice_transaction: ctx.accounts.choice_transaction.key(),
+                mint,
+                destination: accounts[destination_index as usize].key(),
+                amount,
+            }),
+        }
+    }
+
+    // The wallet signs for itself, so a transaction could assign or allocate it to hand it to
+    // another program, which could then move its lamports without counting against the limits
+    require!(
+        *ctx.accounts.wallet.owner == system_program::ID && ctx.accounts.wallet.data_is_empty(),
+        ErrorCode::WalletOwnerChanged
+    );
+    let lamports_spent = lamports_before.saturating_sub(ctx.accounts.wallet.lamports());
+    let token_outflows = if has_limits {
+        token_outflows(&ctx.accounts.wallet, accounts, &token_accounts_before)?
+    } else {
+        vec![]
+    };
+    let spent = |mint: &Pubkey| {
+        if *mint == Pubkey::default() {
+            lamports_spent
+        } else {
+            token_outflows
+                .iter()
+                .filter(|(outflow_mint, _)| outflow_mint == mint)
+                .map(|(_, amount)| *amount)
+                .fold(0, u64::saturating_add)
+        }
+    };
+
+    if let Some(policy) = &policy {
+        policy.assert_spent(&spent)?;
+    }
+
+    if !ctx.accounts.organization_wallet.spending_limits.is_empty() {
+        let clock = Clock::get()?;
+        for limit in ctx.accounts.organization_wallet.spending_limits.iter_mut() {
+            limit.spend(spent(&limit.mint), &clock)?;
+        }
+        // Rolling limits record their spends
+        resize_to_fit(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.organization_wallet,
+            ErrorCode::InvalidDataIncrease,
+        )?;
+    }
+
     Ok(())
 }
+
+fn execute_action<'info>(
+    action: &ActionV0,
+    wallet: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    wallet_seeds: &[&[u8]],
+) -> Result<()> {
+    let account = |index: u8| {
+        accounts
+            .get(index as usize)
+            .cloned()
+            .ok_or(error!(ErrorCode::InvalidAccount))
+    };
+
+    match *action {
+        ActionV0::TransferV0 {
+            mint,
+            amount,
+            program_index,
+            source_index,
+            destination_index,
+            mint_index,
+        } => {
+            let program = account(program_index)?;
+            let source = account(source_index)?;
+            let destination = account(destination_index)?;
+            if mint == Pubkey::default() {
+                require_eq!(*program.key, system_program::ID, ErrorCode::InvalidAccount);
+                require_eq!(*source.key, wallet.key(), ErrorCode::InvalidAccount);
+                system_program::transfer(
+                    CpiContext::new_with_signer(
+                        program,
+                        system_program::Transfer {
+                            from: source,
+                            to: destination,
+                        },
+                        &[wallet_seeds],
+                    ),
+                    amount,
+                )
+            } else {
+                let mint_account = account(mint_index)?;
+                require!(
+                    *program.key == token::ID || *program.key == token_2022::ID,
+                    ErrorCode::InvalidAccount
+                );
+                require_eq!(*mint_account.key, mint, ErrorCode::InvalidAccount);
+                require_eq!(*mint_account.owner, *program.key, ErrorCode::InvalidAccount);
+                // Only the wallet's own tokens of `mint` can be sent, not tokens of another
+                // account the wallet happens to be a delegate of
+                require_eq!(*source.owner, *program.key, ErrorCode::InvalidAccount);
+                let source_account = TokenAccount::try_deserialize(&mut &source.data.borrow()[..])?;
+                require!(
+                    source_account.owner == wallet.key() && source_account.mint == mint,
+                    ErrorCode::InvalidAccount
+                );
+                let decimals =
+                    Mint::try_deserialize(&mut &mint_account.data.borrow()[..])?.decimals;
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        program,
+                        TransferChecked {
+                            from: source,
+                            mint: mint_account,
+                            to: destination,
+                            authority: wallet.clone(),
+                        },
+                        &[wallet_seeds],
+                    ),
+                    amount,
+                    decimals,
+                )
+            }
+        }
+    }
+}
+
+/// A token account owned by the wallet, as it was before the transaction
+struct WalletTokenAccount {
+    key: Pubkey,
+    mint: Pubkey,
+    amount: u64,
+    delegate: COption<Pubkey>,
+    close_authority: COption<Pubkey>,
+}
+
+/// Every passed token account owned by the wallet, whatever its mint, so that none can be
+/// handed over during the transaction
+fn wallet_token_accounts(
+    wallet: &AccountInfo,
+    accounts: &[AccountInfo],
+) -> Vec<WalletTokenAccount> {
+    let mut token_accounts: Vec<WalletTokenAccount> = vec![];
+    for account in accounts {
+        if (*account.owner != token::ID && *account.owner != token_2022::ID)
+            || token_accounts
+                .iter()
+                .any(|token_account| token_account.key == account.key())
+        {
+            continue;
+        }
+        if let Ok(token_account) = TokenAccount::try_deserialize(&mut &account.data.borrow()[..]) {
+            if token_account.owner == wallet.key() {
+                token_accounts.push(WalletTokenAccount {
+                    key: account.key(),
+                    mint: token_account.mint,
+                    amount: token_account.amount,
+                    delegate: token_account.delegate,
+                    close_authority: token_account.close_authority,
+                });
+            }
+        }
+    }
+
+    token_accounts
+}
+
+/// Tokens that left each wallet token account, as (mint, amount). Inflows do not offset
+/// outflows, and closed accounts count their whole balance as spent. Fails if a token
+/// account changed owner, delegate or close authority, since those could move its
+/// tokens later without counting against the limits
+fn token_outflows(
+    wallet: &AccountInfo,
+    accounts: &[AccountInfo],
+    before: &[WalletTokenAccount],
+) -> Result<Vec<(Pubkey, u64)>> {
+    before
+        .iter()
+        .map(|token_account| {
+            let account = accounts
+                .iter()
+                .find(|account| account.key() == token_account.key)
+                .ok_or(ErrorCode::InvalidAccount)?;
+            let amount = if account.lamports() == 0 {
+                0
+            } else {
+                match TokenAccount::try_deserialize(&mut &account.data.borrow()[..]) {
+                    Ok(after) => {
+                        require!(
+                            after.owner == wallet.key()
+                                && after.delegate == token_account.delegate
+                                && after.close_authority == token_account.close_authority,
+                            ErrorCode::TokenAuthorityChanged
+                        );
+                        after.amount
+                    }
+                    // Closed in the transaction
+                    Err(_) => 0,
+                }
+            };
+
+            Ok((
+                token_account.mint,
+                token_account.amount.saturating_sub(amount),
+            ))
+        })
+        .collect()
+}
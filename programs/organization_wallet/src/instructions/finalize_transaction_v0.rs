@@ -0,0 +1,30 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use proposal::{ProposalState, ProposalV0};
+
+#[derive(Accounts)]
+pub struct FinalizeTransactionV0<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+    has_one = owner,
+    constraint = proposal.state == ProposalState::Draft @ ErrorCode::InvalidProposalState,
+  )]
+    pub proposal: Box<Account<'info, ProposalV0>>,
+    #[account(
+    mut,
+    has_one = proposal,
+    constraint = !choice_transaction.finalized @ ErrorCode::TransactionFinalized,
+  )]
+    pub choice_transaction: Box<Account<'info, ChoiceTransactionV0>>,
+}
+
+/// Locks a chunked transaction and records its hash, which voters can check against the
+/// transaction they expect
+pub fn handler(ctx: Context<FinalizeTransactionV0>) -> Result<()> {
+    let choice_transaction = &mut ctx.accounts.choice_transaction;
+    choice_transaction.transaction_hash = choice_transaction.transaction.hash()?;
+    choice_transaction.finalized = true;
+
+    Ok(())
+}
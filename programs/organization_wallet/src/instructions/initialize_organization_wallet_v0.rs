@@ -54,6 +54,10 @@ pub fn handler(
             wallet_bump_seed: wallet_bump,
             proposal_configs: args.proposal_configs,
             bump_seed: ctx.bumps["organization_wallet"],
+            spending_limits: vec![],
+            allowed_programs: vec![],
+            guardian: Pubkey::default(),
+            proposal_config_policies: vec![],
         });
     Ok(())
 }
@@ -0,0 +1,119 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use account_realloc::{realloc_account, serialized_size};
+use anchor_lang::prelude::*;
+use proposal::ProposalV0;
+
+/// Earlier versions padded choice transactions with two pubkeys worth of zeros
+const LEGACY_PADDING: usize = 64;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MigrateChoiceTransactionArgsV0 {
+    pub choice_index: u16,
+    /// Was only part of the address in the previous layout
+    pub transaction_index: u16,
+}
+
+#[derive(Accounts)]
+#[instruction(args: MigrateChoiceTransactionArgsV0)]
+pub struct MigrateChoiceTransactionV0<'info> {
+    /// Pays for the growth of the choice transaction, and receives its rent once it is closed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub owner: Signer<'info>,
+    #[account(
+    has_one = owner,
+  )]
+    pub proposal: Box<Account<'info, ProposalV0>>,
+    #[account(
+    has_one = proposal,
+  )]
+    pub wallet_proposal: Box<Account<'info, WalletProposalV0>>,
+    /// CHECK: Deserialized in the previous layout by the handler
+    #[account(
+    mut,
+    owner = crate::ID,
+    seeds = [b"choice_transaction", wallet_proposal.key().as_ref(), &args.choice_index.to_le_bytes(), &args.transaction_index.to_le_bytes()],
+    bump,
+  )]
+    pub choice_transaction: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Rewrites a choice transaction set before the current `ChoiceTransactionV0` layout, so it
+/// can be executed or closed again. Its offsets become an `AfterResolution` window, and the
+/// transaction is finalized with its hash, as earlier versions could not chunk transactions
+pub fn handler(
+    ctx: Context<MigrateChoiceTransactionV0>,
+    args: MigrateChoiceTransactionArgsV0,
+) -> Result<()> {
+    let info = ctx.accounts.choice_transaction.to_account_info();
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == ChoiceTransactionV0::discriminator(),
+            ErrorCode::NotLegacyTransaction
+        );
+        let mut rest = &data[8..];
+        let legacy = LegacyChoiceTransactionV0::deserialize(&mut rest)
+            .map_err(|_| error!(ErrorCode::NotLegacyTransaction))?;
+        // Accounts in the current layout are sized exactly, without the zero padding
+        require!(
+            rest.len() == LEGACY_PADDING && rest.iter().all(|b| *b == 0),
+            ErrorCode::NotLegacyTransaction
+        );
+        legacy
+    };
+    require_keys_eq!(
+        legacy.wallet_proposal,
+        ctx.accounts.wallet_proposal.key(),
+        ErrorCode::NotLegacyTransaction
+    );
+    require_eq!(
+        legacy.choice_index,
+        args.choice_index,
+        ErrorCode::NotLegacyTransaction
+    );
+
+    let transaction = CompiledTransactionV0 {
+        num_rw_signers: legacy.transaction.num_rw_signers,
+        num_ro_signers: legacy.transaction.num_ro_signers,
+        num_rw: legacy.transaction.num_rw,
+        accounts: legacy.transaction.accounts,
+        instructions: legacy.transaction.instructions,
+        signer_seeds: legacy.transaction.signer_seeds,
+        ..Default::default()
+    };
+    let choice_transaction = ChoiceTransactionV0 {
+        wallet_proposal: legacy.wallet_proposal,
+        proposal: legacy.proposal,
+        organization_wallet: legacy.organization_wallet,
+        payer: ctx.accounts.payer.key(),
+        choice_index: legacy.choice_index,
+        transaction_index: args.transaction_index,
+        execution_window: ExecutionWindowV0::AfterResolution {
+            delay: legacy.allow_execution_offset,
+            expiry: Some(legacy.disable_execution_offset),
+        },
+        bump_seed: legacy.bump_seed,
+        transaction_hash: transaction.hash()?,
+        transaction,
+        finalized: true,
+        vetoed: false,
+    };
+
+    let new_size = serialized_size(&choice_transaction)?;
+    let size = realloc_account(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        new_size,
+    )?;
+    require_eq!(size, new_size, ErrorCode::InvalidDataIncrease);
+
+    let mut data = info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    choice_transaction.try_serialize(&mut writer)?;
+
+    Ok(())
+}
@@ -1,9 +1,25 @@
+pub mod append_transaction_data_v0;
+pub mod clear_choice_transactions_v0;
+pub mod close_expired_transaction_v0;
+pub mod deposit_v0;
 pub mod execute_transaction_v0;
+pub mod finalize_transaction_v0;
 pub mod initialize_organization_wallet_v0;
+pub mod migrate_choice_transaction_v0;
+pub mod remove_transaction_v0;
 pub mod set_transactions_v0;
 pub mod update_organization_wallet_v0;
+pub mod veto_transaction_v0;
 
+pub use append_transaction_data_v0::*;
+pub use clear_choice_transactions_v0::*;
+pub use close_expired_transaction_v0::*;
+pub use deposit_v0::*;
 pub use execute_transaction_v0::*;
+pub use finalize_transaction_v0::*;
 pub use initialize_organization_wallet_v0::*;
+pub use migrate_choice_transaction_v0::*;
+pub use remove_transaction_v0::*;
 pub use set_transactions_v0::*;
 pub use update_organization_wallet_v0::*;
+pub use veto_transaction_v0::*;
@@ -0,0 +1,58 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use proposal::{ProposalState, ProposalV0};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct RemoveTransactionArgsV0 {
+    pub choice_index: u16,
+    pub transaction_index: u16,
+}
+
+#[derive(Accounts)]
+#[instruction(args: RemoveTransactionArgsV0)]
+pub struct RemoveTransactionV0<'info> {
+    /// CHECK: Checked via has one, receives the rent it paid for the choice transaction
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    pub owner: Signer<'info>,
+    #[account(
+    has_one = owner,
+    constraint = proposal.state == ProposalState::Draft @ ErrorCode::InvalidProposalState,
+  )]
+    pub proposal: Box<Account<'info, ProposalV0>>,
+    #[account(
+    mut,
+    has_one = proposal,
+  )]
+    pub wallet_proposal: Box<Account<'info, WalletProposalV0>>,
+    #[account(
+    mut,
+    close = payer,
+    has_one = wallet_proposal,
+    has_one = payer,
+    seeds = [b"choice_transaction", wallet_proposal.key().as_ref(), &args.choice_index.to_le_bytes(), &args.transaction_index.to_le_bytes()],
+    bump = choice_transaction.bump_seed,
+  )]
+    pub choice_transaction: Box<Account<'info, ChoiceTransactionV0>>,
+}
+
+/// Only the last transaction of a choice can be removed, so transaction indices stay contiguous.
+/// Remove from the end, or clear the choice, to replace earlier transactions
+pub fn handler(ctx: Context<RemoveTransactionV0>, args: RemoveTransactionArgsV0) -> Result<()> {
+    if let Some(num_transactions) = ctx
+        .accounts
+        .wallet_proposal
+        .num_transactions_by_choice
+        .get_mut(args.choice_index as usize)
+    {
+        require_eq!(
+            args.transaction_index + 1,
+            *num_transactions,
+            ErrorCode::InvalidTransactionIndex
+        );
+        *num_transactions -= 1;
+    }
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use crate::error::ErrorCode;
-use crate::{resize_to_fit::resize_to_fit, state::*};
+use crate::lookup_table::{assert_active, resolve_lookups};
+use crate::state::*;
+use account_realloc::resize_to_fit;
 use anchor_lang::prelude::*;
 use proposal::{ProposalState, ProposalV0};
 
@@ -14,20 +16,24 @@ pub struct CompiledTransactionArgV0 {
     pub num_ro_signers: u8,
     pub num_rw: u8,
     /// Accounts will come from remaining accounts, which allows for lookup tables
-    /// and such to reduce size of txn call here
+    /// and such to reduce size of txn call here. The lookup tables follow them
     pub instructions: Vec<CompiledInstructionV0>,
     pub signer_seeds: Vec<Vec<Vec<u8>>>,
+    /// Program ids must be in the remaining accounts, other accounts may be loaded from tables
+    pub address_table_lookups: Vec<CompiledLookupTableV0>,
+    pub actions: Vec<ActionV0>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct SetTransactionsArgsV0 {
     pub choice_index: u16,
     pub transaction_index: u16,
-    // Cannot be executed until this offset from proposal end
-    pub allow_execution_offset: u32,
-    // Cannot be executed after this offset
-    pub disable_execution_offset: u32,
+    pub execution_window: ExecutionWindowV0,
     pub transaction: CompiledTransactionArgV0,
+    /// Whether transactions of each choice must execute in index order
+    pub ordered: Option<bool>,
+    /// Leaves the transaction open for `append_transaction_data_v0` until it is finalized
+    pub chunked: Option<bool>,
 }
 
 #[derive(Accounts)]
@@ -66,61 +72,99 @@ pub struct SetTransactionsV0<'info> {
 }
 
 pub fn handler(ctx: Context<SetTransactionsV0>, args: SetTransactionsArgsV0) -> Result<()> {
-    require!(
-        ctx.accounts
-            .wallet_proposal
-            .num_transactions_by_choice
-            .get(args.choice_index as usize)
-            .unwrap_or(&0)
-            <= &args.transaction_index,
+    args.execution_window.validate()?;
+    if let Some(policy) = ctx
+        .accounts
+        .organization_wallet
+        .policy(&ctx.accounts.proposal.proposal_config)
+    {
+        policy.assert_can_queue(&args.execution_window)?;
+    }
+
+    let proposal = ctx.accounts.proposal.key();
+    let organization_wallet = ctx.accounts.organization_wallet.key();
+    let num_choices = ctx.accounts.proposal.choices.len();
+    let wallet_proposal = &mut ctx.accounts.wallet_proposal;
+    if wallet_proposal.num_transactions_by_choice.is_empty() {
+        wallet_proposal.proposal = proposal;
+        wallet_proposal.organization_wallet = organization_wallet;
+        wallet_proposal.num_transactions_by_choice = vec![0; num_choices];
+        wallet_proposal.executed_count_by_choice = vec![0; num_choices];
+    }
+    if let Some(ordered) = args.ordered {
+        wallet_proposal.ordered = ordered;
+    }
+
+    let num_transactions = wallet_proposal
+        .num_transactions_by_choice
+        .get_mut(args.choice_index as usize)
+        .ok_or(ErrorCode::InvalidTransactionIndex)?;
+    // Transactions are appended in order, so indices have no gaps
+    require_eq!(
+        args.transaction_index,
+        *num_transactions,
         ErrorCode::InvalidTransactionIndex
     );
+    *num_transactions += 1;
+
+    // Lookup tables are passed after the accounts of the transaction, and their addresses
+    // are resolved now so voters approve the accounts that will be used
+    let num_accounts = ctx
+        .remaining_accounts
+        .len()
+        .checked_sub(args.transaction.address_table_lookups.len())
+        .ok_or(ErrorCode::InvalidLookupTable)?;
+    let (accounts, tables) = ctx.remaining_accounts.split_at(num_accounts);
+    for table in tables {
+        assert_active(table)?;
+    }
+    let (loaded_writable, loaded_readonly) =
+        resolve_lookups(&args.transaction.address_table_lookups, tables)?;
+    let accounts = accounts.iter().map(|a| a.key()).collect::<Vec<_>>();
+    let transaction = CompiledTransactionV0 {
+        num_rw_signers: args.transaction.num_rw_signers,
+        num_ro_signers: args.transaction.num_ro_signers,
+        num_rw: args.transaction.num_rw,
+        instructions: args.transaction.instructions,
+        signer_seeds: args.transaction.signer_seeds,
+        address_table_lookups: args.transaction.address_table_lookups,
+        actions: args.transaction.actions,
+        accounts,
+        loaded_writable,
+        loaded_readonly,
+    };
+    ctx.accounts
+        .organization_wallet
+        .assert_programs_allowed(&transaction)?;
+    let finalized = !args.chunked.unwrap_or(false);
+    let transaction_hash = if finalized {
+        transaction.hash()?
+    } else {
+        [0; 32]
+    };
+
     ctx.accounts
         .choice_transaction
         .set_inner(ChoiceTransactionV0 {
             choice_index: args.choice_index,
+            transaction_index: args.transaction_index,
             organization_wallet: ctx.accounts.organization_wallet.key(),
             proposal: ctx.accounts.proposal.key(),
             wallet_proposal: ctx.accounts.wallet_proposal.key(),
+            payer: ctx.accounts.payer.key(),
             bump_seed: ctx.bumps["choice_transaction"],
-            disable_execution_offset: args.disable_execution_offset,
-            allow_execution_offset: args.allow_execution_offset,
-            transaction: CompiledTransactionV0 {
-                num_rw_signers: args.transaction.num_rw_signers,
-                num_ro_signers: args.transaction.num_ro_signers,
-                num_rw: args.transaction.num_rw,
-                instructions: args.transaction.instructions,
-                signer_seeds: args.transaction.signer_seeds,
-                accounts: ctx.remaining_accounts.iter().map(|a| a.key()).collect(),
-            },
+            execution_window: args.execution_window,
+            transaction,
+            finalized,
+            transaction_hash,
+            vetoed: false,
         });
-    ctx.accounts.wallet_proposal.set_inner(WalletProposalV0 {
-        proposal: ctx.accounts.proposal.key(),
-        organization_wallet: ctx.accounts.organization_wallet.key(),
-        num_transactions_by_choice: ctx
-            .accounts
-            .wallet_proposal
-            .num_transactions_by_choice
-            .iter()
-            .enumerate()
-            .map(|(i, n)| {
-                if i == args.choice_index as usize {
-                    if args.transaction_index == *n {
-                        n + 1
-                    } else {
-                        *n
-                    }
-                } else {
-                    *n
-                }
-            })
-            .collect(),
-    });
 
     resize_to_fit(
         &ctx.accounts.payer.to_account_info(),
         &ctx.accounts.system_program.to_account_info(),
         &ctx.accounts.choice_transaction,
+        ErrorCode::InvalidDataIncrease,
     )?;
 
     Ok(())
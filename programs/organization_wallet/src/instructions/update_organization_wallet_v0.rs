@@ -1,5 +1,6 @@
 use crate::error::ErrorCode;
-use crate::{resize_to_fit::resize_to_fit, state::OrganizationWalletV0};
+use crate::state::*;
+use account_realloc::resize_to_fit;
 use anchor_lang::prelude::*;
 use organization::state::OrganizationV0;
 
@@ -7,6 +8,22 @@ use organization::state::OrganizationV0;
 pub struct UpdateOrganizationWalletArgsV0 {
   pub name: Option<String>,
   pub proposal_configs: Option<Vec<Pubkey>>,
+  /// Replaces the spending limits, starting new windows
+  pub spending_limits: Option<Vec<SpendingLimitArgV0>>,
+  /// Replaces the programs transactions may invoke. Empty allows any program
+  pub allowed_programs: Option<Vec<Pubkey>>,
+  /// `Pubkey::default()` removes the guardian
+  pub guardian: Option<Pubkey>,
+  /// Replaces the policies. Each must be for a different one of the wallet's proposal configs
+  pub proposal_config_policies: Option<Vec<ProposalConfigPolicyV0>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SpendingLimitArgV0 {
+  /// `Pubkey::default()` limits lamports
+  pub mint: Pubkey,
+  pub max_amount: u64,
+  pub window: SpendingWindowV0,
 }
 
 #[derive(Accounts)]
@@ -38,11 +55,52 @@ pub fn handler(
   if let Some(proposal_configs) = args.proposal_configs {
     ctx.accounts.organization_wallet.proposal_configs = proposal_configs;
   }
+  if let Some(spending_limits) = args.spending_limits {
+    let clock = Clock::get()?;
+    ctx.accounts.organization_wallet.spending_limits = spending_limits
+      .into_iter()
+      .map(|limit| SpendingLimitV0 {
+        window_start: clock.epoch as i64,
+        mint: limit.mint,
+        max_amount: limit.max_amount,
+        window: limit.window,
+        spent: 0,
+        spends: vec![],
+      })
+      .collect();
+  }
+  if let Some(allowed_programs) = args.allowed_programs {
+    ctx.accounts.organization_wallet.allowed_programs = allowed_programs;
+  }
+  if let Some(guardian) = args.guardian {
+    ctx.accounts.organization_wallet.guardian = guardian;
+  }
+  if let Some(proposal_config_policies) = args.proposal_config_policies {
+    ctx.accounts.organization_wallet.proposal_config_policies = proposal_config_policies;
+  }
+  let organization_wallet = &ctx.accounts.organization_wallet;
+  let policies = &organization_wallet.proposal_config_policies;
+  for (index, policy) in policies.iter().enumerate() {
+    require!(
+      organization_wallet
+        .proposal_configs
+        .contains(&policy.proposal_config),
+      ErrorCode::InvalidProposalConfig
+    );
+    // Only the first policy of a config would be enforced
+    require!(
+      policies[..index]
+        .iter()
+        .all(|other| other.proposal_config != policy.proposal_config),
+      ErrorCode::InvalidProposalConfig
+    );
+  }
 
   resize_to_fit(
     &ctx.accounts.payer.to_account_info(),
     &ctx.accounts.system_program.to_account_info(),
     &ctx.accounts.organization_wallet,
+    ErrorCode::InvalidDataIncrease,
   )?;
 
   Ok(())
@@ -0,0 +1,68 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use account_realloc::grow_to_fit;
+use anchor_lang::prelude::*;
+use proposal::{ProposalState, ProposalV0};
+
+#[derive(Accounts)]
+pub struct VetoTransactionV0<'info> {
+    /// Pays for any growth of the wallet proposal needed to record the veto
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+    #[account(
+    has_one = guardian,
+  )]
+    pub organization_wallet: Box<Account<'info, OrganizationWalletV0>>,
+    pub proposal: Box<Account<'info, ProposalV0>>,
+    #[account(mut)]
+    pub wallet_proposal: Box<Account<'info, WalletProposalV0>>,
+    #[account(
+    mut,
+    has_one = proposal,
+    has_one = organization_wallet,
+    has_one = wallet_proposal,
+    constraint = !choice_transaction.vetoed @ ErrorCode::TransactionVetoed,
+    constraint = match &proposal.state {
+      ProposalState::Resolved { end_ts, .. } => {
+        let (allow_ts, _) = choice_transaction.execution_window.bounds(*end_ts);
+        Clock::get()?.unix_timestamp < allow_ts
+      }
+      _ => false,
+    } @ ErrorCode::VetoWindowClosed,
+  )]
+    pub choice_transaction: Box<Account<'info, ChoiceTransactionV0>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Marks a passed transaction as vetoed during its delay, so it can never be executed.
+/// The transaction stays open as a record of the veto, its rent is refunded to its payer
+/// once it is closed through `close_expired_transaction_v0`
+pub fn handler(ctx: Context<VetoTransactionV0>) -> Result<()> {
+    ctx.accounts.choice_transaction.vetoed = true;
+
+    let choice_index = ctx.accounts.choice_transaction.choice_index as usize;
+    let wallet_proposal = &mut ctx.accounts.wallet_proposal;
+    if wallet_proposal.vetoed_by_choice.len() <= choice_index {
+        wallet_proposal
+            .vetoed_by_choice
+            .resize(choice_index + 1, vec![]);
+    }
+    wallet_proposal.vetoed_by_choice[choice_index]
+        .push(ctx.accounts.choice_transaction.transaction_index);
+
+    // Only grow, the padding of the wallet proposal belongs to whoever paid for it
+    grow_to_fit(
+        &ctx.accounts.guardian.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.wallet_proposal,
+        ErrorCode::InvalidDataIncrease,
+    )?;
+
+    msg!(
+        "Transaction {} vetoed by {}",
+        ctx.accounts.choice_transaction.key(),
+        ctx.accounts.guardian.key()
+    );
+
+    Ok(())
+}
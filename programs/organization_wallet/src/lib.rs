@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 pub mod error;
 pub mod instructions;
-pub mod resize_to_fit;
+pub mod lookup_table;
 pub mod state;
 
 use instructions::*;
@@ -27,10 +27,54 @@ pub mod organization_wallet {
         set_transactions_v0::handler(ctx, args)
     }
 
+    pub fn append_transaction_data_v0(
+        ctx: Context<AppendTransactionDataV0>,
+        args: AppendTransactionDataArgsV0,
+    ) -> Result<()> {
+        append_transaction_data_v0::handler(ctx, args)
+    }
+
+    pub fn finalize_transaction_v0(ctx: Context<FinalizeTransactionV0>) -> Result<()> {
+        finalize_transaction_v0::handler(ctx)
+    }
+
+    pub fn remove_transaction_v0(
+        ctx: Context<RemoveTransactionV0>,
+        args: RemoveTransactionArgsV0,
+    ) -> Result<()> {
+        remove_transaction_v0::handler(ctx, args)
+    }
+
+    pub fn clear_choice_transactions_v0<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClearChoiceTransactionsV0<'info>>,
+        args: ClearChoiceTransactionsArgsV0,
+    ) -> Result<()> {
+        clear_choice_transactions_v0::handler(ctx, args)
+    }
+
+    pub fn deposit_v0(ctx: Context<DepositV0>, args: DepositArgsV0) -> Result<()> {
+        deposit_v0::handler(ctx, args)
+    }
+
     pub fn execute_transaction_v0(ctx: Context<ExecuteTransactionV0>) -> Result<()> {
         execute_transaction_v0::handler(ctx)
     }
 
+    pub fn close_expired_transaction_v0(ctx: Context<CloseExpiredTransactionV0>) -> Result<()> {
+        close_expired_transaction_v0::handler(ctx)
+    }
+
+    pub fn veto_transaction_v0(ctx: Context<VetoTransactionV0>) -> Result<()> {
+        veto_transaction_v0::handler(ctx)
+    }
+
+    pub fn migrate_choice_transaction_v0(
+        ctx: Context<MigrateChoiceTransactionV0>,
+        args: MigrateChoiceTransactionArgsV0,
+    ) -> Result<()> {
+        migrate_choice_transaction_v0::handler(ctx, args)
+    }
+
   pub fn update_organization_wallet_v0(
     ctx: Context<UpdateOrganizationWalletV0>,
     args: UpdateOrganizationWalletArgsV0,
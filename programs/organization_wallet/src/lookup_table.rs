@@ -0,0 +1,71 @@
+use crate::error::ErrorCode;
+use crate::state::CompiledLookupTableV0;
+use anchor_lang::prelude::*;
+use std::str::FromStr;
+
+pub fn address_lookup_table_program_id() -> Pubkey {
+    Pubkey::from_str("AddressLookupTab1e1111111111111111111111111").unwrap()
+}
+
+// Addresses follow the table's metadata
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+// The deactivation slot follows the u32 discriminator, and is u64::MAX while active
+const DEACTIVATION_SLOT_OFFSET: usize = 4;
+
+/// Address at `index` in the data of a lookup table account
+pub fn lookup_table_address(data: &[u8], index: u8) -> Option<Pubkey> {
+    let offset = LOOKUP_TABLE_META_SIZE + index as usize * 32;
+    data.get(offset..offset + 32)
+        .and_then(|bytes| Pubkey::try_from(bytes).ok())
+}
+
+/// Requires the lookup table to be active, so it cannot be closed while its addresses
+/// are pinned by a transaction
+pub fn assert_active(table: &AccountInfo) -> Result<()> {
+    let data = table.data.borrow();
+    let deactivation_slot = data
+        .get(DEACTIVATION_SLOT_OFFSET..DEACTIVATION_SLOT_OFFSET + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or_else(|| error!(ErrorCode::InvalidLookupTable))?;
+    require_eq!(
+        deactivation_slot,
+        u64::MAX,
+        ErrorCode::LookupTableDeactivated
+    );
+
+    Ok(())
+}
+
+/// Resolves the accounts loaded through lookup tables, as (writable, read only). As in v0
+/// messages, the writable accounts of every table come before any read only account
+pub fn resolve_lookups(
+    lookups: &[CompiledLookupTableV0],
+    tables: &[AccountInfo],
+) -> Result<(Vec<Pubkey>, Vec<Pubkey>)> {
+    require_eq!(lookups.len(), tables.len(), ErrorCode::InvalidLookupTable);
+
+    let mut writable = vec![];
+    let mut readonly = vec![];
+    for (lookup, table) in lookups.iter().zip(tables) {
+        require_keys_eq!(table.key(), lookup.table, ErrorCode::InvalidLookupTable);
+        require_keys_eq!(
+            *table.owner,
+            address_lookup_table_program_id(),
+            ErrorCode::InvalidLookupTable
+        );
+
+        let data = table.data.borrow();
+        let address_at = |index: &u8| {
+            lookup_table_address(&data, *index).ok_or_else(|| error!(ErrorCode::InvalidLookupTable))
+        };
+        for index in &lookup.writable_indexes {
+            writable.push(address_at(index)?);
+        }
+        for index in &lookup.readonly_indexes {
+            readonly.push(address_at(index)?);
+        }
+    }
+
+    Ok((writable, readonly))
+}
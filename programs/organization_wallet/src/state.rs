@@ -1,4 +1,6 @@
-use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+use proposal::ProposalState;
 
 #[account]
 #[derive(Default)]
@@ -10,6 +12,175 @@ pub struct OrganizationWalletV0 {
     pub name: String,
     pub bump_seed: u8,
     pub wallet_bump_seed: u8,
+    /// Maximum outflows of the wallet, enforced on every executed transaction
+    pub spending_limits: Vec<SpendingLimitV0>,
+    /// Programs transactions may invoke. Empty allows any program
+    pub allowed_programs: Vec<Pubkey>,
+    /// May veto transactions before their execution window opens. `Pubkey::default()` for none
+    pub guardian: Pubkey,
+    /// Restrictions on proposals of the listed configs. Configs without a policy are unrestricted
+    pub proposal_config_policies: Vec<ProposalConfigPolicyV0>,
+}
+
+impl OrganizationWalletV0 {
+    pub fn policy(&self, proposal_config: &Pubkey) -> Option<&ProposalConfigPolicyV0> {
+        self.proposal_config_policies
+            .iter()
+            .find(|policy| policy.proposal_config == *proposal_config)
+    }
+
+    pub fn assert_programs_allowed(&self, transaction: &CompiledTransactionV0) -> Result<()> {
+        let program_indexes = transaction
+            .instructions
+            .iter()
+            .map(|ix| ix.program_id_index)
+            .chain(transaction.actions.iter().map(|action| match action {
+                ActionV0::TransferV0 { program_index, .. } => *program_index,
+            }));
+        for index in program_indexes {
+            let program_id = transaction
+                .accounts
+                .get(index as usize)
+                .ok_or(ErrorCode::InvalidAccount)?;
+            require!(
+                self.allowed_programs.is_empty() || self.allowed_programs.contains(program_id),
+                ErrorCode::ProgramNotAllowed
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ProposalConfigPolicyV0 {
+    pub proposal_config: Pubkey,
+    /// Proposals of this config only signal, and cannot queue transactions
+    pub signal_only: bool,
+    /// Maximum lamports each transaction may move out of the wallet
+    pub max_lamports: Option<u64>,
+    /// Minimum seconds between resolution and execution
+    pub min_delay: u32,
+    /// Maximum tokens of each mint a transaction may move out of the wallet's token
+    /// accounts. Mints without a cap are not limited by the policy
+    pub max_token_amounts: Vec<TokenCapV0>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct TokenCapV0 {
+    pub mint: Pubkey,
+    pub max_amount: u64,
+}
+
+impl ProposalConfigPolicyV0 {
+    pub fn assert_can_queue(&self, execution_window: &ExecutionWindowV0) -> Result<()> {
+        require!(!self.signal_only, ErrorCode::PolicyViolation);
+        // Absolute windows are only known relative to resolution once executed
+        if let ExecutionWindowV0::AfterResolution { delay, .. } = execution_window {
+            require_gte!(*delay, self.min_delay, ErrorCode::PolicyViolation);
+        }
+
+        Ok(())
+    }
+
+    pub fn assert_can_execute(&self, end_ts: i64, curr_ts: i64) -> Result<()> {
+        require!(!self.signal_only, ErrorCode::PolicyViolation);
+        require_gte!(
+            curr_ts,
+            end_ts + self.min_delay as i64,
+            ErrorCode::PolicyViolation
+        );
+
+        Ok(())
+    }
+
+    /// `spent` is the amount of a mint that left the wallet, `Pubkey::default()` for lamports
+    pub fn assert_spent(&self, spent: impl Fn(&Pubkey) -> u64) -> Result<()> {
+        if let Some(max_lamports) = self.max_lamports {
+            require_gte!(
+                max_lamports,
+                spent(&Pubkey::default()),
+                ErrorCode::SpendingLimitExceeded
+            );
+        }
+        for cap in &self.max_token_amounts {
+            require_gte!(
+                cap.max_amount,
+                spent(&cap.mint),
+                ErrorCode::SpendingLimitExceeded
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum SpendingWindowV0 {
+    /// Resets every epoch
+    Epoch,
+    /// Limits the amount spent in any `seconds` long period ending now
+    Rolling { seconds: u32 },
+}
+
+/// An amount spent under a rolling limit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SpendV0 {
+    pub timestamp: i64,
+    pub amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SpendingLimitV0 {
+    /// `Pubkey::default()` limits lamports, otherwise the wallet's token accounts of this mint
+    pub mint: Pubkey,
+    pub max_amount: u64,
+    pub window: SpendingWindowV0,
+    /// Epoch the current window started at, for epoch windows
+    pub window_start: i64,
+    /// Spent in the current window
+    pub spent: u64,
+    /// Spends still inside a rolling window, oldest first. Empty for epoch windows
+    pub spends: Vec<SpendV0>,
+}
+
+impl SpendingLimitV0 {
+    pub fn spend(&mut self, amount: u64, clock: &Clock) -> Result<()> {
+        match self.window {
+            SpendingWindowV0::Epoch => {
+                let epoch = clock.epoch as i64;
+                if epoch != self.window_start {
+                    self.window_start = epoch;
+                    self.spent = 0;
+                }
+                self.spent = self.spent.saturating_add(amount);
+            }
+            SpendingWindowV0::Rolling { seconds } => {
+                let now = clock.unix_timestamp;
+                self.spends
+                    .retain(|spend| spend.timestamp + seconds as i64 > now);
+                if amount > 0 {
+                    self.spends.push(SpendV0 {
+                        timestamp: now,
+                        amount,
+                    });
+                }
+                self.spent = self
+                    .spends
+                    .iter()
+                    .map(|spend| spend.amount)
+                    .fold(0, u64::saturating_add);
+            }
+        }
+
+        require_gte!(
+            self.max_amount,
+            self.spent,
+            ErrorCode::SpendingLimitExceeded
+        );
+
+        Ok(())
+    }
 }
 
 #[macro_export]
@@ -42,21 +213,128 @@ pub struct WalletProposalV0 {
     pub proposal: Pubkey,
     pub organization_wallet: Pubkey,
     pub num_transactions_by_choice: Vec<u16>,
+    /// Transactions of a choice must execute in index order
+    pub ordered: bool,
+    pub executed_count_by_choice: Vec<u16>,
+    /// Indices of the vetoed transactions of each choice, skipped by ordered execution
+    pub vetoed_by_choice: Vec<Vec<u16>>,
+}
+
+/// When a transaction of a resolved proposal can be executed. Transactions that never
+/// expire can still be reclaimed once their choice loses or the proposal is cancelled
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ExecutionWindowV0 {
+    /// Seconds after the proposal resolved. No expiry keeps the transaction executable forever
+    AfterResolution { delay: u32, expiry: Option<u32> },
+    /// Unix timestamps, independent of when the proposal resolved
+    Absolute { from: i64, until: Option<i64> },
+}
+
+impl Default for ExecutionWindowV0 {
+    fn default() -> Self {
+        ExecutionWindowV0::AfterResolution {
+            delay: 0,
+            expiry: None,
+        }
+    }
+}
+
+impl ExecutionWindowV0 {
+    pub fn validate(&self) -> Result<()> {
+        let valid = match *self {
+            ExecutionWindowV0::AfterResolution { delay, expiry } => {
+                expiry.map_or(true, |expiry| delay < expiry)
+            }
+            ExecutionWindowV0::Absolute { from, until } => until.map_or(true, |until| from < until),
+        };
+        require!(valid, ErrorCode::InvalidExecutionWindow);
+        Ok(())
+    }
+
+    /// The (allow, disable) timestamps of the window for a proposal resolved at `end_ts`
+    pub fn bounds(&self, end_ts: i64) -> (i64, Option<i64>) {
+        match *self {
+            ExecutionWindowV0::AfterResolution { delay, expiry } => (
+                end_ts + delay as i64,
+                expiry.map(|expiry| end_ts + expiry as i64),
+            ),
+            ExecutionWindowV0::Absolute { from, until } => (from, until),
+        }
+    }
+
+    pub fn is_open(&self, end_ts: i64, curr_ts: i64) -> bool {
+        let (allow_ts, _) = self.bounds(end_ts);
+        curr_ts >= allow_ts && !self.is_closed(end_ts, curr_ts)
+    }
+
+    pub fn is_closed(&self, end_ts: i64, curr_ts: i64) -> bool {
+        let (_, disable_ts) = self.bounds(end_ts);
+        disable_ts.map_or(false, |disable_ts| curr_ts >= disable_ts)
+    }
 }
 
 #[account]
 #[derive(Default)]
 pub struct ChoiceTransactionV0 {
+    pub wallet_proposal: Pubkey,
+    pub proposal: Pubkey,
+    pub organization_wallet: Pubkey,
+    /// Paid the rent for this account, refunded when it is closed
+    pub payer: Pubkey,
+    pub choice_index: u16,
+    pub transaction_index: u16,
+    pub execution_window: ExecutionWindowV0,
+    pub bump_seed: u8,
+    pub transaction: CompiledTransactionV0,
+    /// Chunked transactions cannot be executed until their data is finalized
+    pub finalized: bool,
+    /// Sha256 of `transaction` once finalized, so voters can verify the payload
+    pub transaction_hash: [u8; 32],
+    /// Vetoed by the guardian, so it can never be executed
+    pub vetoed: bool,
+}
+
+impl ChoiceTransactionV0 {
+    /// Whether this transaction can never be executed. Executed transactions are not
+    /// expired, see `CloseExpiredTransactionV0`
+    pub fn is_expired(&self, state: &ProposalState, curr_ts: i64) -> bool {
+        match state {
+            ProposalState::Cancelled => true,
+            _ if self.vetoed => true,
+            // Data can only be appended while the proposal is a draft
+            _ if !self.finalized && *state != ProposalState::Draft => true,
+            ProposalState::Resolved { choices, end_ts } => {
+                !choices.contains(&self.choice_index)
+                    || self.execution_window.is_closed(*end_ts, curr_ts)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// `ChoiceTransactionV0` as stored before transactions gained a payer, a transaction index,
+/// execution windows, chunking, hashes and vetoes. Only read by
+/// `migrate_choice_transaction_v0`, after the shared discriminator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct LegacyChoiceTransactionV0 {
     pub wallet_proposal: Pubkey,
     pub proposal: Pubkey,
     pub organization_wallet: Pubkey,
     pub choice_index: u16,
-    // Cannot be executed until this offset from proposal end
     pub allow_execution_offset: u32,
-    // Cannot be executed after this offset
     pub disable_execution_offset: u32,
     pub bump_seed: u8,
-    pub transaction: CompiledTransactionV0,
+    pub transaction: LegacyCompiledTransactionV0,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct LegacyCompiledTransactionV0 {
+    pub num_rw_signers: u8,
+    pub num_ro_signers: u8,
+    pub num_rw: u8,
+    pub accounts: Vec<Pubkey>,
+    pub instructions: Vec<CompiledInstructionV0>,
+    pub signer_seeds: Vec<Vec<Vec<u8>>>,
 }
 
 #[macro_export]
@@ -105,6 +383,64 @@ pub struct CompiledTransactionV0 {
     /// and the bump you pass and account should be consistent with this. But to save space
     /// in the instruction, they should be ommitted here. See tests for examples
     pub signer_seeds: Vec<Vec<Vec<u8>>>,
+    /// Accounts loaded from address lookup tables. Instruction indices past `accounts`
+    /// refer to the loaded writable accounts, then the loaded read only accounts
+    pub address_table_lookups: Vec<CompiledLookupTableV0>,
+    /// Typed actions executed after `instructions`, so clients can display them without
+    /// decoding instruction data
+    pub actions: Vec<ActionV0>,
+    /// Addresses resolved from `address_table_lookups` when the transaction was set, so they
+    /// are part of the hash voters approve and cannot change by the time it executes
+    pub loaded_writable: Vec<Pubkey>,
+    pub loaded_readonly: Vec<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ActionV0 {
+    /// Sends `amount` out of the wallet. Indices point into the transaction keys
+    TransferV0 {
+        /// `Pubkey::default()` transfers lamports, otherwise tokens of this mint
+        mint: Pubkey,
+        amount: u64,
+        /// The system program for lamports, otherwise the token program of the mint
+        program_index: u8,
+        /// The wallet, or its token account for `mint`
+        source_index: u8,
+        /// The recipient, or its token account for `mint`
+        destination_index: u8,
+        /// The mint account. Ignored for lamports
+        mint_index: u8,
+    },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct CompiledLookupTableV0 {
+    pub table: Pubkey,
+    /// Indices into the table of accounts to load as writable
+    pub writable_indexes: Vec<u8>,
+    /// Indices into the table of accounts to load as read only
+    pub readonly_indexes: Vec<u8>,
+}
+
+impl CompiledTransactionV0 {
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        Ok(hashv(&[&self.try_to_vec()?]).to_bytes())
+    }
+}
+
+/// Proof that a choice transaction was executed, and by whom
+#[account]
+#[derive(Default, InitSpace)]
+pub struct ExecutionReceiptV0 {
+    pub choice_transaction: Pubkey,
+    pub proposal: Pubkey,
+    pub executor: Pubkey,
+    pub executed_at: i64,
+    /// Sha256 of the executed `CompiledTransactionV0`
+    pub transaction_hash: [u8; 32],
+    pub choice_index: u16,
+    pub transaction_index: u16,
+    pub bump_seed: u8,
 }
 
 #[account]
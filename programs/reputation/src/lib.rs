@@ -29,6 +29,11 @@ pub struct InitializeRepConfigArgsV0 {
     pub proposal_min_reputation_gate: Option<u64>,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct InitializeReputationManagerArgsV0 {
+    pub namespace: Pubkey,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct RepVoteArgsV0 {
     pub choice: u16,
@@ -55,6 +60,7 @@ pub mod reputation {
             voting_rep_reward: args.voting_rep_reward,
             proposal_success_reward: args.proposal_success_reward,
             token_required: args.token_required,
+            proposal_min_reputation_gate: args.proposal_min_reputation_gate,
             token_controller: args.token_controller,
             bump: ctx.bumps["rep_config"],
         });
@@ -62,6 +68,23 @@ pub mod reputation {
         Ok(())
     }
 
+    pub fn initialize_reputation_manager_v0(
+        ctx: Context<InitializeReputationManagerV0>,
+        args: InitializeReputationManagerArgsV0,
+    ) -> Result<()> {
+        ctx.accounts
+            .reputation_manager
+            .set_inner(ReputationManagerV0 {
+                namespace: args.namespace,
+                repuation_config: ctx.accounts.rep_config.key(),
+                wallet: ctx.accounts.wallet.key(),
+                reputation: ContributionReputationV0::default(),
+                bump: ctx.bumps["reputation_manager"],
+            });
+
+        Ok(())
+    }
+
     pub fn add_to_receipt(ctx: Context<AddToReceiptV0>, args: AddToReceiptArgsV0) -> Result<()> {
         if ctx.accounts.receipt.to_account_infos().is_empty() {
             ctx.accounts.receipt.set_inner(ReceiptV0 {
@@ -206,6 +229,28 @@ pub struct InitializeRepConfigV0<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct InitializeReputationManagerV0<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Any wallet can have a reputation, it starts out empty
+    pub wallet: UncheckedAccount<'info>,
+    #[account(
+      seeds = [b"rep_config", rep_config.name.as_bytes()],
+      bump = rep_config.bump
+    )]
+    pub rep_config: Box<Account<'info, ReputationConfigV0>>,
+    #[account(
+      init,
+      payer = payer,
+      space = ReputationManagerV0::space(),
+      seeds = [b"reputation_manager", rep_config.key().as_ref(), wallet.key().as_ref()],
+      bump
+    )]
+    pub reputation_manager: Box<Account<'info, ReputationManagerV0>>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 #[instruction(args: AddToReceiptArgsV0)]
 pub struct AddToReceiptV0<'info> {
@@ -295,11 +340,32 @@ pub struct ReputationConfigV0 {
     */
     pub token_required: bool,
     pub bump: u8,
+    /*
+        Minimum reputation needed to create proposals
+    */
+    pub proposal_min_reputation_gate: Option<u64>,
 }
 
 impl ReputationConfigV0 {
     pub fn space(name: &str) -> usize {
-        8 + 32 + 4 + name.len() + 1 + 1 + 1 + 1 + std::mem::size_of::<Option<Pubkey>>() + 1
+        8 + 32 + 4 + name.len() + 1 + 1 + 1 + 1 + std::mem::size_of::<Option<Pubkey>>() + 1 + 8 + 1
+    }
+
+    /// Contribution score of a reputation, weighted by this config's rewards
+    pub fn score(&self, reputation: &ContributionReputationV0) -> u64 {
+        reputation
+            .proposal_votes
+            .saturating_mul(self.voting_rep_reward as u64)
+            .saturating_add(
+                reputation
+                    .proposal_votes_in_alignment
+                    .saturating_mul(self.voting_in_alignment_reward as u64),
+            )
+            .saturating_add(
+                reputation
+                    .proposals_created_success
+                    .saturating_mul(self.proposal_success_reward as u64),
+            )
     }
 }
 
@@ -322,7 +388,15 @@ pub struct ReputationManagerV0 {
 
 impl ReputationManagerV0 {
     pub fn space() -> usize {
-        8 + 32 + 32 + 32 + mem::size_of::<ContributionReputationV0>()
+        8 + 32 + 32 + 32 + mem::size_of::<ContributionReputationV0>() + 1
+    }
+
+    pub fn address(rep_config: &Pubkey, wallet: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"reputation_manager", rep_config.as_ref(), wallet.as_ref()],
+            &crate::ID,
+        )
+        .0
     }
 }
 
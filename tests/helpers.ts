@@ -1,4 +1,5 @@
 import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   createAssociatedTokenAccountInstruction,
   createInitializeMintInstruction,
   createMintToCheckedInstruction,
@@ -8,17 +9,24 @@ import {
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import {
+  createCreateInstruction,
   createCreateMasterEditionV3Instruction,
   createCreateMetadataAccountV3Instruction,
+  createDelegateInstruction,
+  createLockInstruction,
   CreateMasterEditionArgs,
   CreateMasterEditionV3InstructionAccounts,
   CreateMetadataAccountArgsV3,
   CreateMetadataAccountV3InstructionAccounts,
+  createMintInstruction,
   createSetAndVerifySizedCollectionItemInstruction,
+  Creator,
   PROGRAM_ADDRESS,
   SetAndVerifySizedCollectionItemInstructionAccounts,
+  TokenStandard,
 } from "@metaplex-foundation/mpl-token-metadata";
 import { Provider, utils, web3 } from "@coral-xyz/anchor";
+import { keccak_256 } from "@noble/hashes/sha3";
 
 export const getCreateMintIx = async (
   mint: web3.PublicKey,
@@ -186,7 +194,8 @@ export const mintNft = async (
   mint: web3.Keypair,
   provider: Provider,
   reciever: web3.PublicKey,
-  uri?: string
+  uri?: string,
+  creators: Creator[] | null = null
 ) => {
   let instructions: web3.TransactionInstruction[] = [];
 
@@ -218,7 +227,7 @@ export const mintNft = async (
         ? uri
         : "https://shdw-drive.genesysgo.net/Avy3TVpFP9M1mjrDFZdswBhdA7kZaJnyiLBPi1XRYqRa/collectionMetadata_49gW2U7ftZ724rMk6bswVayCrTNAu9aGcMiKnSj8XPAG6i5k5pxTtNSUVKZyeWFpn8npz4CVsqzL7CqXfgW33fAM.json",
       sellerFeeBasisPoints: 10000,
-      creators: null,
+      creators,
       collection: {
         key: collection,
         verified: false,
@@ -282,3 +291,255 @@ export const mintNft = async (
   });
   return sig;
 };
+
+const compareHashes = (a: Buffer, b: Buffer) => Buffer.compare(a, b);
+
+/**
+ * Builds the tree of (wallet, multiplier) leaves behind a `merkleWallets` guard.
+ * Siblings are hashed in sorted order, matching the on-chain verification
+ */
+export const buildWalletTree = (
+  entries: { wallet: web3.PublicKey; multiplier: number }[]
+) => {
+  const leaves = entries.map(({ wallet, multiplier }) => {
+    const multiplierBytes = Buffer.alloc(2);
+    multiplierBytes.writeUInt16LE(multiplier);
+    return Buffer.from(
+      keccak_256(Buffer.concat([wallet.toBuffer(), multiplierBytes]))
+    );
+  });
+
+  const layers = [leaves];
+  while (layers[layers.length - 1].length > 1) {
+    const layer = layers[layers.length - 1];
+    const next: Buffer[] = [];
+    for (let i = 0; i < layer.length; i += 2) {
+      if (i + 1 === layer.length) {
+        // An unpaired node moves up a layer unchanged
+        next.push(layer[i]);
+      } else {
+        const pair = [layer[i], layer[i + 1]].sort(compareHashes);
+        next.push(Buffer.from(keccak_256(Buffer.concat(pair))));
+      }
+    }
+    layers.push(next);
+  }
+
+  const root = layers[layers.length - 1][0] || Buffer.alloc(32);
+
+  const getProof = (wallet: web3.PublicKey) => {
+    let index = entries.findIndex((entry) => entry.wallet.equals(wallet));
+    const { multiplier } = entries[index];
+    const proof: number[][] = [];
+    for (const layer of layers.slice(0, -1)) {
+      const sibling = layer[index ^ 1];
+      if (sibling) {
+        proof.push([...sibling]);
+      }
+      index = Math.floor(index / 2);
+    }
+    return { multiplier, proof };
+  };
+
+  return { root: [...root], getProof };
+};
+
+export const getTokenRecordAddress = (
+  mint: web3.PublicKey,
+  token: web3.PublicKey
+) =>
+  web3.PublicKey.findProgramAddressSync(
+    [
+      utils.bytes.utf8.encode("metadata"),
+      new web3.PublicKey(PROGRAM_ADDRESS).toBuffer(),
+      mint.toBuffer(),
+      utils.bytes.utf8.encode("token_record"),
+      token.toBuffer(),
+    ],
+    new web3.PublicKey(PROGRAM_ADDRESS)
+  )[0];
+
+/**
+ * Mints a programmable NFT to `reciever`, returning the accounts its token
+ * record check needs
+ */
+export const mintProgrammableNft = async (
+  mint: web3.Keypair,
+  provider: Provider,
+  reciever: web3.PublicKey,
+  creators: Creator[] | null = null
+) => {
+  const metadata = await getMetadataAddress(mint.publicKey);
+  const masterEdition = await getMasterEditionAddress(mint.publicKey);
+  const token = await getAssociatedTokenAddress(mint.publicKey, reciever);
+  const tokenRecord = getTokenRecordAddress(mint.publicKey, token);
+
+  const createIx = createCreateInstruction(
+    {
+      metadata,
+      masterEdition,
+      mint: mint.publicKey,
+      authority: provider.publicKey,
+      payer: provider.publicKey,
+      updateAuthority: provider.publicKey,
+      sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      splTokenProgram: TOKEN_PROGRAM_ID,
+    },
+    {
+      createArgs: {
+        __kind: "V1",
+        assetData: {
+          name: "name",
+          symbol: "DS",
+          uri: "https://example.com",
+          sellerFeeBasisPoints: 0,
+          creators,
+          primarySaleHappened: false,
+          isMutable: true,
+          tokenStandard: TokenStandard.ProgrammableNonFungible,
+          collection: null,
+          uses: null,
+          collectionDetails: null,
+          ruleSet: null,
+        },
+        decimals: 0,
+        printSupply: { __kind: "Zero" },
+      },
+    }
+  );
+  // The mint is created by the instruction, so it must sign
+  createIx.keys = createIx.keys.map((key) =>
+    key.pubkey.equals(mint.publicKey) ? { ...key, isSigner: true } : key
+  );
+
+  const mintIx = createMintInstruction(
+    {
+      token,
+      tokenOwner: reciever,
+      metadata,
+      masterEdition,
+      tokenRecord,
+      mint: mint.publicKey,
+      authority: provider.publicKey,
+      payer: provider.publicKey,
+      sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      splTokenProgram: TOKEN_PROGRAM_ID,
+      splAtaProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    },
+    {
+      mintArgs: {
+        __kind: "V1",
+        amount: 1,
+        authorizationData: null,
+      },
+    }
+  );
+
+  const computeIx = web3.ComputeBudgetProgram.setComputeUnitLimit({
+    units: 400000,
+  });
+
+  await provider.sendAndConfirm(
+    new web3.Transaction().add(computeIx, createIx, mintIx),
+    [mint]
+  );
+
+  return { metadata, masterEdition, token, tokenRecord };
+};
+
+/**
+ * Sets a utility delegate on a programmable NFT owned by the provider's wallet,
+ * optionally locking it with that delegate
+ */
+export const delegateProgrammableNft = async (
+  mint: web3.PublicKey,
+  provider: Provider,
+  delegate: web3.Keypair,
+  lock = false
+) => {
+  const metadata = await getMetadataAddress(mint);
+  const masterEdition = await getMasterEditionAddress(mint);
+  const token = await getAssociatedTokenAddress(mint, provider.publicKey);
+  const tokenRecord = getTokenRecordAddress(mint, token);
+
+  const tx = new web3.Transaction().add(
+    createDelegateInstruction(
+      {
+        delegate: delegate.publicKey,
+        metadata,
+        masterEdition,
+        tokenRecord,
+        mint,
+        token,
+        authority: provider.publicKey,
+        payer: provider.publicKey,
+        sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        splTokenProgram: TOKEN_PROGRAM_ID,
+      },
+      {
+        delegateArgs: {
+          __kind: "UtilityV1",
+          amount: 1,
+          authorizationData: null,
+        },
+      }
+    )
+  );
+  if (lock) {
+    tx.add(
+      createLockInstruction(
+        {
+          authority: delegate.publicKey,
+          tokenOwner: provider.publicKey,
+          token,
+          mint,
+          metadata,
+          edition: masterEdition,
+          tokenRecord,
+          payer: provider.publicKey,
+          sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          splTokenProgram: TOKEN_PROGRAM_ID,
+        },
+        {
+          lockArgs: {
+            __kind: "V1",
+            authorizationData: null,
+          },
+        }
+      )
+    );
+  }
+
+  await provider.sendAndConfirm(tx, lock ? [delegate] : []);
+};
+
+/**
+ * Nodes of a full binary tree of keccak hashes, with empty leaves as zeroes,
+ * as stored by a concurrent merkle tree without a canopy
+ */
+export const getCompressedNftProof = (
+  leaves: Buffer[],
+  index: number,
+  maxDepth: number
+) => {
+  let layer = [...leaves];
+  let empty = Buffer.alloc(32);
+  const proof: Buffer[] = [];
+  for (let depth = 0; depth < maxDepth; depth++) {
+    const siblingIndex = (index >> depth) ^ 1;
+    proof.push(layer[siblingIndex] || empty);
+
+    const next: Buffer[] = [];
+    for (let i = 0; i < layer.length; i += 2) {
+      next.push(
+        Buffer.from(
+          keccak_256(Buffer.concat([layer[i], layer[i + 1] || empty]))
+        )
+      );
+    }
+    layer = next;
+    empty = Buffer.from(keccak_256(Buffer.concat([empty, empty])));
+  }
+
+  return { root: layer[0] || empty, proof };
+};
@@ -7,6 +7,7 @@ import {
 } from "@solana/web3.js";
 import { OrgNftGuard } from "../target/types/org_nft_guard";
 import { Organization } from "../target/types/organization";
+import { Reputation } from "../target/types/reputation";
 import { expect } from "chai";
 import { random, sample } from "lodash";
 import {
@@ -15,16 +16,44 @@ import {
 } from "@helium/proposal-sdk";
 import { organizationKey } from "@helium/organization-sdk";
 import { IDL as PROPOSAL_IDL } from "./idls/proposal";
-import { getMetadataAddress, mintCollectionNft, mintNft } from "./helpers";
 import {
+  buildWalletTree,
+  delegateProgrammableNft,
+  getCompressedNftProof,
+  getMetadataAddress,
+  mintCollectionNft,
+  mintNft,
+  mintProgrammableNft,
+} from "./helpers";
+import {
+  computeCompressedNFTHash,
+  createCreateTreeInstruction,
+  createMintV1Instruction,
+  getLeafAssetId,
+  PROGRAM_ID as BUBBLEGUM_PROGRAM_ID,
+  TokenProgramVersion,
+  TokenStandard,
+} from "@metaplex-foundation/mpl-bubblegum";
+import {
+  createAllocTreeIx,
+  SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+  SPL_NOOP_PROGRAM_ID,
+} from "@solana/spl-account-compression";
+import {
+  createApproveInstruction,
   createAssociatedTokenAccountInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
   getAssociatedTokenAddressSync,
+  getMinimumBalanceForRentExemptMint,
+  MINT_SIZE,
+  TOKEN_2022_PROGRAM_ID,
 } from "@solana/spl-token";
 import { createMint } from "./utils";
 
-const orgNftGuardKey = (name: string) => {
+const orgNftGuardKey = (authority: PublicKey, name: string) => {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from("guard"), Buffer.from(name)],
+    [Buffer.from("guard"), authority.toBuffer(), Buffer.from(name)],
     anchor.workspace.OrgNftGuard.programId
   );
 };
@@ -36,8 +65,17 @@ const proposalConfigKey = (name: string) => {
   );
 };
 
-const initializeGuard = async ({ provider, name, guardType }) => {
-  const [nftGuard, bump] = orgNftGuardKey(name);
+const initializeGuard = async ({
+  provider,
+  name,
+  guardType,
+  authority = provider.wallet.publicKey,
+  minWeight = new anchor.BN(0),
+  template = null,
+  limits = null,
+  signers = [],
+}) => {
+  const [nftGuard, bump] = orgNftGuardKey(authority, name);
 
   const program = anchor.workspace.OrgNftGuard as anchor.Program<OrgNftGuard>;
 
@@ -45,12 +83,17 @@ const initializeGuard = async ({ provider, name, guardType }) => {
     .initializeGuardV0({
       name,
       guardType,
+      minWeight,
+      template,
+      limits,
     })
     .accountsStrict({
       payer: provider.wallet.publicKey,
       nftGuard,
+      authority,
       systemProgram: SystemProgram.programId,
     })
+    .signers(signers)
     .rpc();
 
   return { guard: nftGuard, bump };
@@ -121,6 +164,69 @@ const initializeOrganization = async ({
   return { organization, bump };
 };
 
+const simulateLogs = async (method) => {
+  let logs: string;
+
+  try {
+    await method.simulate();
+  } catch (err) {
+    ({ logs } = err.simulationResponse || {});
+  }
+
+  return logs;
+};
+
+const createToken2022Mint = async (
+  provider: anchor.AnchorProvider,
+  { amount, decimals, target }
+): Promise<[PublicKey, PublicKey]> => {
+  const mint = Keypair.generate();
+  const tokenAccount = getAssociatedTokenAddressSync(
+    mint.publicKey,
+    target,
+    true,
+    TOKEN_2022_PROGRAM_ID
+  );
+  const me = provider.wallet.publicKey;
+
+  await provider.sendAndConfirm(
+    new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: me,
+        newAccountPubkey: mint.publicKey,
+        space: MINT_SIZE,
+        lamports: await getMinimumBalanceForRentExemptMint(provider.connection),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializeMint2Instruction(
+        mint.publicKey,
+        decimals,
+        me,
+        null,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createAssociatedTokenAccountInstruction(
+        me,
+        tokenAccount,
+        target,
+        mint.publicKey,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createMintToInstruction(
+        mint.publicKey,
+        tokenAccount,
+        me,
+        amount,
+        [],
+        TOKEN_2022_PROGRAM_ID
+      )
+    ),
+    [mint]
+  );
+
+  return [tokenAccount, mint.publicKey];
+};
+
 describe("org nft guard", () => {
   anchor.setProvider(anchor.AnchorProvider.env());
   const provider = anchor.getProvider() as anchor.AnchorProvider;
@@ -128,6 +234,52 @@ describe("org nft guard", () => {
 
   const program = anchor.workspace.OrgNftGuard as anchor.Program<OrgNftGuard>;
 
+  const initializeProposalByProof = ({
+    name,
+    guard,
+    proposalConfig,
+    organization,
+    remainingAccounts = [],
+  }) => {
+    const buffer = Buffer.allocUnsafe(4);
+    buffer.writeUInt32LE(0); // num proposals
+    const [proposal] = proposalKey(organization, buffer);
+
+    return program.methods
+      .initializeProposalByProofV0({
+        name,
+        uri: "https://example.com",
+        maxChoicesPerVoter: 1,
+        choices: [
+          { name: "Aye", uri: null },
+          { name: "Nay", uri: null },
+        ],
+        tags: [],
+      })
+      .accountsStrict({
+        initializeProposalBase: {
+          payer: me,
+          guard,
+          guardUsage: null,
+          proposal,
+          owner: me,
+          proposalConfig,
+          organization,
+          systemProgram: SystemProgram.programId,
+          proposalProgram: PROPOSAL_PROGRAM_ID,
+          organizationProgram: anchor.workspace.Organization.programId,
+        },
+        proposer: me,
+      })
+      .remainingAccounts(
+        remainingAccounts.map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: false,
+        }))
+      );
+  };
+
   it("initializes org nft guard with collection mint", async () => {
     let name = "test" + Math.random();
     const address = Keypair.generate().publicKey;
@@ -152,11 +304,163 @@ describe("org nft guard", () => {
     const guardData = account.guardType.collectionMint.guardData;
 
     expect(account.name).to.eq(name);
+    expect(account.authority.equals(me)).to.be.true;
     expect(account.bump).to.eq(bump);
     expect(guardData[0].address.equals(address)).to.be.true;
     expect(guardData[0].multiplier).to.eq(multiplier);
   });
 
+  describe("update and close", () => {
+    let name: string;
+    let guard: PublicKey;
+
+    beforeEach(async () => {
+      name = "test" + Math.random();
+
+      ({ guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          walletList: {
+            guardData: [{ address: me, multiplier: 1 }],
+          },
+        },
+      }));
+    });
+
+    it("adds entries to the guard", async () => {
+      const guardData = Array.from({ length: 20 }).map(() => ({
+        address: Keypair.generate().publicKey,
+        multiplier: 1,
+      }));
+
+      await program.methods
+        .updateGuardV0({
+          authority: null,
+          guardType: { walletList: { guardData } },
+          minWeight: null,
+          template: null,
+          limits: null,
+        })
+        .accountsStrict({
+          payer: me,
+          guard,
+          authority: me,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const account = await program.account.guardV0.fetch(guard);
+
+      expect(account.guardType.walletList.guardData).to.have.length(20);
+    });
+
+    it("removes entries from the guard", async () => {
+      await program.methods
+        .updateGuardV0({
+          authority: null,
+          guardType: { walletList: { guardData: [] } },
+          minWeight: null,
+          template: null,
+          limits: null,
+        })
+        .accountsStrict({
+          payer: me,
+          guard,
+          authority: me,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const account = await program.account.guardV0.fetch(guard);
+
+      expect(account.guardType.walletList.guardData).to.have.length(0);
+    });
+
+    it("requires the authority to sign to initialize its guards", async () => {
+      const authority = Keypair.generate();
+      const name = "test" + Math.random();
+      const [nftGuard] = orgNftGuardKey(authority.publicKey, name);
+      let err: Error | undefined;
+
+      try {
+        await program.methods
+          .initializeGuardV0({
+            name,
+            guardType: { permissive: {} },
+            minWeight: new anchor.BN(0),
+            template: null,
+            limits: null,
+          })
+          .accountsStrict({
+            payer: me,
+            nftGuard,
+            authority: authority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+      } catch (e) {
+        err = e;
+      }
+      expect(err?.message).to.match(/Signature verification failed/);
+
+      await initializeGuard({
+        provider,
+        name,
+        guardType: { permissive: {} },
+        authority: authority.publicKey,
+        signers: [authority],
+      });
+      const account = await program.account.guardV0.fetch(nftGuard);
+      expect(account.authority.toBase58()).to.eq(
+        authority.publicKey.toBase58()
+      );
+    });
+
+    it("fails to update if not the authority", async () => {
+      const authority = Keypair.generate();
+      let logs: string;
+
+      try {
+        await program.methods
+          .updateGuardV0({
+            authority: authority.publicKey,
+            guardType: null,
+            minWeight: null,
+            template: null,
+            limits: null,
+          })
+          .accountsStrict({
+            payer: me,
+            guard,
+            authority: authority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/caused by account: guard\..*ConstraintHasOne/);
+    });
+
+    it("closes the guard", async () => {
+      await program.methods
+        .closeGuardV0()
+        .accountsStrict({
+          refund: me,
+          guard,
+          authority: me,
+        })
+        .rpc();
+
+      const account = await provider.connection.getAccountInfo(guard);
+
+      expect(account).to.be.null;
+    });
+  });
+
   describe("with permissive guard", () => {
     let name: string;
     let guard: PublicKey;
@@ -207,6 +511,7 @@ describe("org nft guard", () => {
           initializeProposalBase: {
             payer: me,
             guard,
+            guardUsage: null,
             proposal,
             owner: me,
             proposalConfig,
@@ -229,66 +534,30 @@ describe("org nft guard", () => {
       expect(account.maxChoicesPerVoter).to.eq(1);
       expect(account.choices.length).to.eq(2);
     });
-  });
-
-  describe("with collection mint guard", () => {
-    const context = async ({ receiver = me }) => {
-      const name = "test" + Math.random();
-
-      const collectionMintKeypair = Keypair.generate();
-      const collectionMint = collectionMintKeypair.publicKey;
-
-      await mintCollectionNft(collectionMintKeypair, provider);
-
-      const mintKeypair = Keypair.generate();
-      const mint = mintKeypair.publicKey;
-
-      await mintNft(collectionMintKeypair, mintKeypair, provider, receiver);
-
-      const { guard } = await initializeGuard({
-        provider,
-        name,
-        guardType: {
-          collectionMint: {
-            guardData: [
-              {
-                address: collectionMint,
-                multiplier: 1,
-              },
-            ],
-          },
-        },
-      });
-
-      const { proposalConfig } = await initalizeProposalConfig({
-        provider,
-        name,
-      });
-
-      const { organization } = await initializeOrganization({
-        provider,
-        name,
-        guard,
-        defaultProposalConfig: proposalConfig,
-      });
 
-      return { name, mint, guard, proposalConfig, organization };
-    };
-
-    it("initializes proposal", async () => {
-      const { name, mint, guard, proposalConfig, organization } = await context(
-        {}
-      );
+    it("initializes proposals after the authority is transferred", async () => {
+      const authority = Keypair.generate();
+      await program.methods
+        .updateGuardV0({
+          authority: authority.publicKey,
+          guardType: null,
+          minWeight: null,
+          template: null,
+          limits: null,
+        })
+        .accountsStrict({
+          payer: me,
+          guard,
+          authority: me,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
 
       const buffer = Buffer.allocUnsafe(4);
       buffer.writeUInt32LE(0); // num proposals
       const [proposal] = proposalKey(organization, buffer);
-
-      const metadata = await getMetadataAddress(mint);
-      const tokenAccount = getAssociatedTokenAddressSync(mint, me);
-
       await program.methods
-        .initializeProposalByNftV0({
+        .initializeProposalPermissivelyV0({
           name,
           uri: "https://example.com",
           maxChoicesPerVoter: 1,
@@ -302,6 +571,7 @@ describe("org nft guard", () => {
           initializeProposalBase: {
             payer: me,
             guard,
+            guardUsage: null,
             proposal,
             owner: me,
             proposalConfig,
@@ -310,12 +580,92 @@ describe("org nft guard", () => {
             proposalProgram: PROPOSAL_PROGRAM_ID,
             organizationProgram: anchor.workspace.Organization.programId,
           },
-          proposer: me,
-          metadata,
-          tokenAccount,
         })
         .rpc();
 
+      const account = await program.account.guardV0.fetch(guard);
+      expect(account.authority.toBase58()).to.eq(
+        authority.publicKey.toBase58()
+      );
+      expect(account.seedAuthority.toBase58()).to.eq(me.toBase58());
+      expect(await provider.connection.getAccountInfo(proposal)).to.not.be
+        .null;
+    });
+  });
+
+  describe("with proposal template", () => {
+    let name: string;
+    let guard: PublicKey;
+    let proposalConfig: PublicKey;
+    let organization: PublicKey;
+    let proposal: PublicKey;
+
+    const initializeProposal = ({
+      choices = ["Yes", "No", "Abstain"],
+      tags = ["treasury"],
+      config = proposalConfig,
+    }) =>
+      program.methods
+        .initializeProposalPermissivelyV0({
+          name,
+          uri: "https://example.com",
+          maxChoicesPerVoter: 1,
+          choices: choices.map((name) => ({ name, uri: null })),
+          tags,
+        })
+        .accountsStrict({
+          initializeProposalBase: {
+            payer: me,
+            guard,
+            guardUsage: null,
+            proposal,
+            owner: me,
+            proposalConfig: config,
+            organization,
+            systemProgram: SystemProgram.programId,
+            proposalProgram: PROPOSAL_PROGRAM_ID,
+            organizationProgram: anchor.workspace.Organization.programId,
+          },
+        });
+
+    beforeEach(async () => {
+      name = "test" + Math.random();
+
+      ({ proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      }));
+
+      ({ guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          permissive: {},
+        },
+        template: {
+          choices: ["Yes", "No", "Abstain"],
+          maxChoicesPerVoter: 1,
+          allowedTags: ["treasury"],
+          maxUriLen: 50,
+          proposalConfig,
+        },
+      }));
+
+      ({ organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      }));
+
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      [proposal] = proposalKey(organization, buffer);
+    });
+
+    it("initializes proposal matching the template", async () => {
+      await initializeProposal({}).rpc();
+
       const proposalProgram = new anchor.Program(
         PROPOSAL_IDL,
         PROPOSAL_PROGRAM_ID
@@ -323,42 +673,319 @@ describe("org nft guard", () => {
 
       const account = await proposalProgram.account.proposalV0.fetch(proposal);
 
-      expect(account.name).to.eq(name);
-      expect(account.maxChoicesPerVoter).to.eq(1);
-      expect(account.choices.length).to.eq(2);
+      expect(account.choices.map((choice) => choice.name)).to.deep.eq([
+        "Yes",
+        "No",
+        "Abstain",
+      ]);
+      expect(account.tags).to.deep.eq(["treasury"]);
     });
 
-    it("fails to initialize proposal with wrong owner", async () => {
-      const receiver = Keypair.generate().publicKey;
-      const { name, mint, guard, proposalConfig, organization } = await context(
-        { receiver }
-      );
+    it("fails to initialize proposal with other choices", async () => {
+      let logs: string;
 
-      const buffer = Buffer.allocUnsafe(4);
-      buffer.writeUInt32LE(0); // num proposals
-      const [proposal] = proposalKey(organization, buffer);
+      try {
+        await initializeProposal({ choices: ["Aye", "Nay"] }).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
 
-      const metadata = await getMetadataAddress(mint);
-      const tokenAccount = getAssociatedTokenAddressSync(mint, receiver);
+      expect(logs).to.match(/ChoicesNotAllowed/);
+    });
 
+    it("fails to initialize proposal with a tag outside the allowlist", async () => {
       let logs: string;
 
       try {
-        await program.methods
-          .initializeProposalByNftV0({
-            name,
-            uri: "https://example.com",
-            maxChoicesPerVoter: 1,
-            choices: [
-              { name: "Aye", uri: null },
-              { name: "Nay", uri: null },
-            ],
-            tags: [],
-          })
-          .accountsStrict({
-            initializeProposalBase: {
+        await initializeProposal({ tags: ["treasury", "other"] }).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/TagNotAllowed/);
+    });
+
+    it("fails to initialize proposal with another proposal config", async () => {
+      const { proposalConfig: config } = await initalizeProposalConfig({
+        provider,
+        name: "other" + Math.random(),
+      });
+
+      let logs: string;
+
+      try {
+        await initializeProposal({ config }).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/ProposalConfigNotAllowed/);
+    });
+  });
+
+  describe("with limits", () => {
+    const guardUsageKey = (guard: PublicKey, proposer: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("guard_usage"), guard.toBuffer(), proposer.toBuffer()],
+        program.programId
+      )[0];
+
+    const context = async (limits) => {
+      const name = "test" + Math.random();
+
+      const { guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          permissive: {},
+        },
+        limits,
+      });
+
+      const { proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      });
+
+      const { organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      });
+
+      const initializeProposal = async (guardUsage: PublicKey | null) => {
+        const { numProposals } = await (
+          anchor.workspace.Organization as anchor.Program<Organization>
+        ).account.organizationV0.fetch(organization);
+        const buffer = Buffer.allocUnsafe(4);
+        buffer.writeUInt32LE(numProposals);
+        const [proposal] = proposalKey(organization, buffer);
+
+        return program.methods
+          .initializeProposalPermissivelyV0({
+            name,
+            uri: "https://example.com",
+            maxChoicesPerVoter: 1,
+            choices: [
+              { name: "Aye", uri: null },
+              { name: "Nay", uri: null },
+            ],
+            tags: [],
+          })
+          .accountsStrict({
+            initializeProposalBase: {
+              payer: me,
+              guard,
+              guardUsage,
+              proposal,
+              owner: me,
+              proposalConfig,
+              organization,
+              systemProgram: SystemProgram.programId,
+              proposalProgram: PROPOSAL_PROGRAM_ID,
+              organizationProgram: anchor.workspace.Organization.programId,
+            },
+          });
+      };
+
+      return { guard, initializeProposal };
+    };
+
+    it("fails to initialize proposal after the guard expired", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const { initializeProposal } = await context({
+        validFrom: new anchor.BN(now - 7200),
+        validUntil: new anchor.BN(now - 3600),
+        maxProposalsPerProposer: null,
+      });
+
+      let logs: string;
+
+      try {
+        await (await initializeProposal(null)).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/GuardNotActive/);
+    });
+
+    it("limits proposals per proposer", async () => {
+      const { guard, initializeProposal } = await context({
+        validFrom: null,
+        validUntil: null,
+        maxProposalsPerProposer: 1,
+      });
+
+      let logs: string;
+
+      try {
+        await (await initializeProposal(null)).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/InvalidGuardUsage/);
+
+      const guardUsage = guardUsageKey(guard, me);
+      await program.methods
+        .initializeGuardUsageV0()
+        .accountsStrict({
+          payer: me,
+          guard,
+          proposer: me,
+          guardUsage,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      await (await initializeProposal(guardUsage)).rpc();
+
+      const account = await program.account.guardUsageV0.fetch(guardUsage);
+      expect(account.numProposals).to.eq(1);
+
+      logs = undefined;
+      try {
+        await (await initializeProposal(guardUsage)).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/ProposalLimitReached/);
+    });
+  });
+
+  describe("with collection mint guard", () => {
+    const context = async ({ receiver = me }) => {
+      const name = "test" + Math.random();
+
+      const collectionMintKeypair = Keypair.generate();
+      const collectionMint = collectionMintKeypair.publicKey;
+
+      await mintCollectionNft(collectionMintKeypair, provider);
+
+      const mintKeypair = Keypair.generate();
+      const mint = mintKeypair.publicKey;
+
+      await mintNft(collectionMintKeypair, mintKeypair, provider, receiver);
+
+      const { guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          collectionMint: {
+            guardData: [
+              {
+                address: collectionMint,
+                multiplier: 1,
+              },
+            ],
+          },
+        },
+      });
+
+      const { proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      });
+
+      const { organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      });
+
+      return { name, mint, guard, proposalConfig, organization };
+    };
+
+    it("initializes proposal", async () => {
+      const { name, mint, guard, proposalConfig, organization } = await context(
+        {}
+      );
+
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      const metadata = await getMetadataAddress(mint);
+      const tokenAccount = getAssociatedTokenAddressSync(mint, me);
+
+      await program.methods
+        .initializeProposalByNftV0({
+          name,
+          uri: "https://example.com",
+          maxChoicesPerVoter: 1,
+          choices: [
+            { name: "Aye", uri: null },
+            { name: "Nay", uri: null },
+          ],
+          tags: [],
+        })
+        .accountsStrict({
+          initializeProposalBase: {
+            payer: me,
+            guard,
+            guardUsage: null,
+            proposal,
+            owner: me,
+            proposalConfig,
+            organization,
+            systemProgram: SystemProgram.programId,
+            proposalProgram: PROPOSAL_PROGRAM_ID,
+            organizationProgram: anchor.workspace.Organization.programId,
+          },
+          proposer: me,
+          metadata,
+          tokenAccount,
+        })
+        .rpc();
+
+      const proposalProgram = new anchor.Program(
+        PROPOSAL_IDL,
+        PROPOSAL_PROGRAM_ID
+      );
+
+      const account = await proposalProgram.account.proposalV0.fetch(proposal);
+
+      expect(account.name).to.eq(name);
+      expect(account.maxChoicesPerVoter).to.eq(1);
+      expect(account.choices.length).to.eq(2);
+    });
+
+    it("fails to initialize proposal with wrong owner", async () => {
+      const receiver = Keypair.generate().publicKey;
+      const { name, mint, guard, proposalConfig, organization } = await context(
+        { receiver }
+      );
+
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      const metadata = await getMetadataAddress(mint);
+      const tokenAccount = getAssociatedTokenAddressSync(mint, receiver);
+
+      let logs: string;
+
+      try {
+        await program.methods
+          .initializeProposalByNftV0({
+            name,
+            uri: "https://example.com",
+            maxChoicesPerVoter: 1,
+            choices: [
+              { name: "Aye", uri: null },
+              { name: "Nay", uri: null },
+            ],
+            tags: [],
+          })
+          .accountsStrict({
+            initializeProposalBase: {
               payer: me,
               guard,
+              guardUsage: null,
               proposal,
               owner: me,
               proposalConfig,
@@ -414,6 +1041,7 @@ describe("org nft guard", () => {
             initializeProposalBase: {
               payer: me,
               guard,
+              guardUsage: null,
               proposal,
               owner: me,
               proposalConfig,
@@ -471,6 +1099,7 @@ describe("org nft guard", () => {
             initializeProposalBase: {
               payer: me,
               guard,
+              guardUsage: null,
               proposal,
               owner: me,
               proposalConfig,
@@ -528,6 +1157,7 @@ describe("org nft guard", () => {
             initializeProposalBase: {
               payer: me,
               guard,
+              guardUsage: null,
               proposal,
               owner: me,
               proposalConfig,
@@ -549,36 +1179,32 @@ describe("org nft guard", () => {
     });
   });
 
-  describe("with mint list guard", () => {
+  describe("with creator guards", () => {
+    // The first creator is unverified, only the second one is verified
     const context = async (
-      { target = me, amount = 10 ** random(0, 9), decimals = random(0, 6) },
-      divisor = new anchor.BN(1)
+      guardType: (creators: {
+        verified: PublicKey;
+        unverified: PublicKey;
+      }) => any
     ) => {
       const name = "test" + Math.random();
+      const unverified = Keypair.generate().publicKey;
 
-      const [tokenAccount, mint] = await createMint(
-        provider.connection,
-        provider.wallet,
-        {
-          amount,
-          decimals,
-          target,
-        }
-      );
+      const collectionMintKeypair = Keypair.generate();
+      await mintCollectionNft(collectionMintKeypair, provider);
+
+      const mintKeypair = Keypair.generate();
+      const mint = mintKeypair.publicKey;
+
+      await mintNft(collectionMintKeypair, mintKeypair, provider, me, null, [
+        { address: unverified, verified: false, share: 0 },
+        { address: me, verified: true, share: 100 },
+      ]);
 
       const { guard } = await initializeGuard({
         provider,
         name,
-        guardType: {
-          mintList: {
-            guardData: [
-              {
-                address: mint,
-                divisor,
-              },
-            ],
-          },
-        },
+        guardType: guardType({ verified: me, unverified }),
       });
 
       const { proposalConfig } = await initalizeProposalConfig({
@@ -593,26 +1219,139 @@ describe("org nft guard", () => {
         defaultProposalConfig: proposalConfig,
       });
 
-      return {
-        name,
-        mint,
-        guard,
-        proposalConfig,
-        organization,
-        tokenAccount,
-      };
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      const method = program.methods
+        .initializeProposalByNftV0({
+          name,
+          uri: "https://example.com",
+          maxChoicesPerVoter: 1,
+          choices: [
+            { name: "Aye", uri: null },
+            { name: "Nay", uri: null },
+          ],
+          tags: [],
+        })
+        .accountsStrict({
+          initializeProposalBase: {
+            payer: me,
+            guard,
+            guardUsage: null,
+            proposal,
+            owner: me,
+            proposalConfig,
+            organization,
+            systemProgram: SystemProgram.programId,
+            proposalProgram: PROPOSAL_PROGRAM_ID,
+            organizationProgram: anchor.workspace.Organization.programId,
+          },
+          proposer: me,
+          metadata: await getMetadataAddress(mint),
+          tokenAccount: getAssociatedTokenAddressSync(mint, me),
+        });
+
+      return { method, proposal };
     };
 
-    it("initializes proposal", async () => {
-      const { name, mint, guard, proposalConfig, organization, tokenAccount } =
-        await context({});
+    it("fails first creator guard when the first creator is not verified", async () => {
+      const { method } = await context(({ unverified }) => ({
+        firstCreatorAddress: {
+          guardData: [{ address: unverified, multiplier: 1 }],
+        },
+      }));
+
+      let logs: string;
+
+      try {
+        await method.simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/FirstCreatorAddressVerificationFailed/);
+    });
+
+    it("initializes proposal with any verified creator guard", async () => {
+      const { method, proposal } = await context(({ verified }) => ({
+        anyVerifiedCreator: {
+          guardData: [{ address: verified, multiplier: 1 }],
+        },
+      }));
+
+      await method.rpc();
+
+      const proposalProgram = new anchor.Program(
+        PROPOSAL_IDL,
+        PROPOSAL_PROGRAM_ID
+      );
+      const account = await proposalProgram.account.proposalV0.fetch(proposal);
+
+      expect(account.choices.length).to.eq(2);
+    });
+
+    it("fails any verified creator guard when the creator is not verified", async () => {
+      const { method } = await context(({ unverified }) => ({
+        anyVerifiedCreator: {
+          guardData: [{ address: unverified, multiplier: 1 }],
+        },
+      }));
+
+      let logs: string;
+
+      try {
+        await method.simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/CreatorVerificationFailed/);
+    });
+  });
+
+  describe("with programmable nfts", () => {
+    const context = async () => {
+      const name = "test" + Math.random();
+
+      const mintKeypair = Keypair.generate();
+      const mint = mintKeypair.publicKey;
+
+      const { metadata, token, tokenRecord } = await mintProgrammableNft(
+        mintKeypair,
+        provider,
+        me,
+        [{ address: me, verified: true, share: 100 }]
+      );
+
+      const { guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          firstCreatorAddress: {
+            guardData: [{ address: me, multiplier: 1 }],
+          },
+        },
+      });
+
+      const { proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      });
+
+      const { organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      });
 
       const buffer = Buffer.allocUnsafe(4);
       buffer.writeUInt32LE(0); // num proposals
       const [proposal] = proposalKey(organization, buffer);
 
-      await program.methods
-        .initializeProposalByTokenV0({
+      const method = program.methods
+        .initializeProposalByNftV0({
           name,
           uri: "https://example.com",
           maxChoicesPerVoter: 1,
@@ -626,6 +1365,7 @@ describe("org nft guard", () => {
           initializeProposalBase: {
             payer: me,
             guard,
+            guardUsage: null,
             proposal,
             owner: me,
             proposalConfig,
@@ -635,40 +1375,312 @@ describe("org nft guard", () => {
             organizationProgram: anchor.workspace.Organization.programId,
           },
           proposer: me,
-          tokenAccount,
+          metadata,
+          tokenAccount: token,
         })
-        .rpc();
+        .remainingAccounts([
+          { pubkey: tokenRecord, isSigner: false, isWritable: false },
+        ]);
+
+      return { mint, method, proposal };
+    };
+
+    it("initializes proposal with an unlocked token record", async () => {
+      const { method, proposal } = await context();
+
+      await method.rpc();
 
       const proposalProgram = new anchor.Program(
         PROPOSAL_IDL,
         PROPOSAL_PROGRAM_ID
       );
+      const account = await proposalProgram.account.proposalV0.fetch(proposal);
+
+      expect(account.choices.length).to.eq(2);
+    });
+
+    it("fails to initialize proposal with a delegated token record", async () => {
+      const { mint, method } = await context();
+      await delegateProgrammableNft(mint, provider, Keypair.generate());
+
+      expect(await simulateLogs(method)).to.match(/InvalidTokenRecord/);
+    });
+
+    it("fails to initialize proposal with a locked token record", async () => {
+      const { mint, method } = await context();
+      await delegateProgrammableNft(mint, provider, Keypair.generate(), true);
+
+      expect(await simulateLogs(method)).to.match(/InvalidTokenRecord/);
+    });
+  });
+
+  describe("with compressed nfts", () => {
+    const maxDepth = 3;
+    const maxBufferSize = 8;
+
+    const metadataArgs = (name: string) => ({
+      name,
+      symbol: "DS",
+      uri: "https://example.com",
+      sellerFeeBasisPoints: 0,
+      primarySaleHappened: false,
+      isMutable: false,
+      editionNonce: null,
+      tokenStandard: TokenStandard.NonFungible,
+      collection: null,
+      uses: null,
+      tokenProgramVersion: TokenProgramVersion.Original,
+      creators: [{ address: me, verified: true, share: 100 }],
+    });
+
+    // The same metadata, as the org-guard program's args
+    const proofMetadata = (name: string) => ({
+      ...metadataArgs(name),
+      tokenStandard: { nonFungible: {} },
+      tokenProgramVersion: { original: {} },
+    });
+
+    const context = async () => {
+      const name = "test" + Math.random();
+      const merkleTreeKeypair = Keypair.generate();
+      const merkleTree = merkleTreeKeypair.publicKey;
+      const [treeAuthority] = PublicKey.findProgramAddressSync(
+        [merkleTree.toBuffer()],
+        BUBBLEGUM_PROGRAM_ID
+      );
+
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          await createAllocTreeIx(
+            provider.connection,
+            merkleTree,
+            me,
+            { maxDepth, maxBufferSize },
+            0
+          ),
+          createCreateTreeInstruction(
+            {
+              treeAuthority,
+              merkleTree,
+              payer: me,
+              treeCreator: me,
+              logWrapper: SPL_NOOP_PROGRAM_ID,
+              compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+            },
+            { maxDepth, maxBufferSize, public: false }
+          )
+        ),
+        [merkleTreeKeypair]
+      );
+
+      const leaves: Buffer[] = [];
+      const mintCompressedNft = async () => {
+        const nonce = leaves.length;
+        await provider.sendAndConfirm(
+          new Transaction().add(
+            createMintV1Instruction(
+              {
+                treeAuthority,
+                leafOwner: me,
+                leafDelegate: me,
+                merkleTree,
+                payer: me,
+                treeDelegate: me,
+                logWrapper: SPL_NOOP_PROGRAM_ID,
+                compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+              },
+              { message: metadataArgs(name) }
+            )
+          )
+        );
+        leaves.push(
+          computeCompressedNFTHash(
+            await getLeafAssetId(merkleTree, new anchor.BN(nonce)),
+            me,
+            me,
+            new anchor.BN(nonce),
+            metadataArgs(name)
+          )
+        );
+
+        return nonce;
+      };
+
+      const { guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          firstCreatorAddress: {
+            guardData: [{ address: me, multiplier: 1 }],
+          },
+        },
+      });
+
+      const { proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      });
+
+      const { organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      });
 
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      const initializeProposal = ({
+        nonce,
+        index = nonce,
+        metadataName = name,
+        proof,
+      }: {
+        nonce: number;
+        index?: number;
+        metadataName?: string;
+        proof: Buffer[];
+      }) =>
+        program.methods
+          .initializeProposalByCompressedNftV0(
+            {
+              name,
+              uri: "https://example.com",
+              maxChoicesPerVoter: 1,
+              choices: [
+                { name: "Aye", uri: null },
+                { name: "Nay", uri: null },
+              ],
+              tags: [],
+            },
+            {
+              nonce: new anchor.BN(nonce),
+              index,
+              delegate: me,
+              metadata: proofMetadata(metadataName),
+            }
+          )
+          .accountsStrict({
+            initializeProposalBase: {
+              payer: me,
+              guard,
+              guardUsage: null,
+              proposal,
+              owner: me,
+              proposalConfig,
+              organization,
+              systemProgram: SystemProgram.programId,
+              proposalProgram: PROPOSAL_PROGRAM_ID,
+              organizationProgram: anchor.workspace.Organization.programId,
+            },
+            proposer: me,
+            merkleTree,
+          })
+          .remainingAccounts(
+            proof.map((node) => ({
+              pubkey: new PublicKey(node),
+              isSigner: false,
+              isWritable: false,
+            }))
+          );
+
+      return { leaves, proposal, mintCompressedNft, initializeProposal };
+    };
+
+    it("initializes proposal with a valid proof", async () => {
+      const { leaves, proposal, mintCompressedNft, initializeProposal } =
+        await context();
+      const nonce = await mintCompressedNft();
+      await mintCompressedNft();
+      const { proof } = getCompressedNftProof(leaves, nonce, maxDepth);
+
+      await initializeProposal({ nonce, proof }).rpc();
+
+      const proposalProgram = new anchor.Program(
+        PROPOSAL_IDL,
+        PROPOSAL_PROGRAM_ID
+      );
       const account = await proposalProgram.account.proposalV0.fetch(proposal);
 
-      expect(account.name).to.eq(name);
-      expect(account.maxChoicesPerVoter).to.eq(1);
       expect(account.choices.length).to.eq(2);
     });
+
+    it("fails to initialize proposal with the wrong index", async () => {
+      const { leaves, mintCompressedNft, initializeProposal } = await context();
+      const nonce = await mintCompressedNft();
+      await mintCompressedNft();
+      const { proof } = getCompressedNftProof(leaves, nonce, maxDepth);
+
+      const logs = await simulateLogs(
+        initializeProposal({ nonce, index: 1, proof })
+      );
+
+      expect(logs).to.match(/CompressedNftVerificationFailed/);
+    });
+
+    it("fails to initialize proposal with a leaf outside the root", async () => {
+      const { leaves, mintCompressedNft, initializeProposal } = await context();
+      const nonce = await mintCompressedNft();
+      const { proof } = getCompressedNftProof(leaves, nonce, maxDepth);
+
+      const logs = await simulateLogs(
+        initializeProposal({ nonce, metadataName: "forged", proof })
+      );
+
+      expect(logs).to.match(/CompressedNftVerificationFailed/);
+    });
+
+    it("fails to initialize proposal with a stale root in the changelog buffer", async () => {
+      const { leaves, mintCompressedNft, initializeProposal } = await context();
+      const nonce = await mintCompressedNft();
+      // Proves the leaf against the root before the second mint, which is still
+      // in the tree's changelog buffer but no longer its current root
+      const { proof } = getCompressedNftProof(leaves, nonce, maxDepth);
+      await mintCompressedNft();
+
+      const logs = await simulateLogs(initializeProposal({ nonce, proof }));
+
+      expect(logs).to.match(/CompressedNftVerificationFailed/);
+    });
   });
 
-  describe("with wallet list guard", () => {
-    const context = async ({ address = me, multiplier = new anchor.BN(1) }) => {
+  describe("with mint list guard", () => {
+    const context = async (
+      {
+        target = me,
+        amount = 10 ** random(0, 9),
+        decimals = random(0, 6),
+        token2022 = false,
+      },
+      divisor = new anchor.BN(1),
+      minWeight = new anchor.BN(0)
+    ) => {
       const name = "test" + Math.random();
 
+      const [tokenAccount, mint] = token2022
+        ? await createToken2022Mint(provider, { amount, decimals, target })
+        : await createMint(provider.connection, provider.wallet, {
+            amount,
+            decimals,
+            target,
+          });
+
       const { guard } = await initializeGuard({
         provider,
         name,
         guardType: {
-          walletList: {
+          mintList: {
             guardData: [
               {
-                address,
-                multiplier,
+                address: mint,
+                divisor,
               },
             ],
           },
         },
+        minWeight,
       });
 
       const { proposalConfig } = await initalizeProposalConfig({
@@ -685,21 +1697,24 @@ describe("org nft guard", () => {
 
       return {
         name,
+        mint,
         guard,
         proposalConfig,
         organization,
+        tokenAccount,
       };
     };
 
     it("initializes proposal", async () => {
-      const { name, guard, proposalConfig, organization } = await context({});
+      const { name, mint, guard, proposalConfig, organization, tokenAccount } =
+        await context({});
 
       const buffer = Buffer.allocUnsafe(4);
       buffer.writeUInt32LE(0); // num proposals
       const [proposal] = proposalKey(organization, buffer);
 
       await program.methods
-        .initializeProposalByWalletV0({
+        .initializeProposalByTokenV0({
           name,
           uri: "https://example.com",
           maxChoicesPerVoter: 1,
@@ -713,6 +1728,7 @@ describe("org nft guard", () => {
           initializeProposalBase: {
             payer: me,
             guard,
+            guardUsage: null,
             proposal,
             owner: me,
             proposalConfig,
@@ -722,6 +1738,7 @@ describe("org nft guard", () => {
             organizationProgram: anchor.workspace.Organization.programId,
           },
           proposer: me,
+          tokenAccount,
         })
         .rpc();
 
@@ -736,5 +1753,837 @@ describe("org nft guard", () => {
       expect(account.maxChoicesPerVoter).to.eq(1);
       expect(account.choices.length).to.eq(2);
     });
+
+    it("initializes proposal with a Token-2022 mint", async () => {
+      const { name, guard, proposalConfig, organization, tokenAccount } =
+        await context({ amount: 10, decimals: 0, token2022: true });
+
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      await program.methods
+        .initializeProposalByTokenV0({
+          name,
+          uri: "https://example.com",
+          maxChoicesPerVoter: 1,
+          choices: [
+            { name: "Aye", uri: null },
+            { name: "Nay", uri: null },
+          ],
+          tags: [],
+        })
+        .accountsStrict({
+          initializeProposalBase: {
+            payer: me,
+            guard,
+            guardUsage: null,
+            proposal,
+            owner: me,
+            proposalConfig,
+            organization,
+            systemProgram: SystemProgram.programId,
+            proposalProgram: PROPOSAL_PROGRAM_ID,
+            organizationProgram: anchor.workspace.Organization.programId,
+          },
+          proposer: me,
+          tokenAccount,
+        })
+        .rpc();
+
+      const proposalProgram = new anchor.Program(
+        PROPOSAL_IDL,
+        PROPOSAL_PROGRAM_ID
+      );
+
+      const account = await proposalProgram.account.proposalV0.fetch(proposal);
+
+      expect(account.name).to.eq(name);
+    });
+
+    it("initializes proposal with delegated tokens", async () => {
+      const owner = Keypair.generate();
+      const { name, guard, proposalConfig, organization, tokenAccount } =
+        await context({ target: owner.publicKey, amount: 10, decimals: 0 });
+
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          createApproveInstruction(tokenAccount, me, owner.publicKey, 5)
+        ),
+        [owner]
+      );
+
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      await program.methods
+        .initializeProposalByTokenV0({
+          name,
+          uri: "https://example.com",
+          maxChoicesPerVoter: 1,
+          choices: [
+            { name: "Aye", uri: null },
+            { name: "Nay", uri: null },
+          ],
+          tags: [],
+        })
+        .accountsStrict({
+          initializeProposalBase: {
+            payer: me,
+            guard,
+            guardUsage: null,
+            proposal,
+            owner: me,
+            proposalConfig,
+            organization,
+            systemProgram: SystemProgram.programId,
+            proposalProgram: PROPOSAL_PROGRAM_ID,
+            organizationProgram: anchor.workspace.Organization.programId,
+          },
+          proposer: me,
+          tokenAccount,
+        })
+        .rpc();
+    });
+
+    it("fails to initialize proposal below the minimum weight", async () => {
+      const { name, guard, proposalConfig, organization, tokenAccount } =
+        await context(
+          { amount: 100, decimals: 0 },
+          new anchor.BN(10),
+          new anchor.BN(20)
+        );
+
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      let logs: string;
+
+      try {
+        await program.methods
+          .initializeProposalByTokenV0({
+            name,
+            uri: "https://example.com",
+            maxChoicesPerVoter: 1,
+            choices: [
+              { name: "Aye", uri: null },
+              { name: "Nay", uri: null },
+            ],
+            tags: [],
+          })
+          .accountsStrict({
+            initializeProposalBase: {
+              payer: me,
+              guard,
+              guardUsage: null,
+              proposal,
+              owner: me,
+              proposalConfig,
+              organization,
+              systemProgram: SystemProgram.programId,
+              proposalProgram: PROPOSAL_PROGRAM_ID,
+              organizationProgram: anchor.workspace.Organization.programId,
+            },
+            proposer: me,
+            tokenAccount,
+          })
+          .simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/InsufficientWeight/);
+    });
+  });
+
+  describe("with wallet list guard", () => {
+    const context = async ({ address = me, multiplier = new anchor.BN(1) }) => {
+      const name = "test" + Math.random();
+
+      const { guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          walletList: {
+            guardData: [
+              {
+                address,
+                multiplier,
+              },
+            ],
+          },
+        },
+      });
+
+      const { proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      });
+
+      const { organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      });
+
+      return {
+        name,
+        guard,
+        proposalConfig,
+        organization,
+      };
+    };
+
+    it("initializes proposal", async () => {
+      const { name, guard, proposalConfig, organization } = await context({});
+
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      await program.methods
+        .initializeProposalByWalletV0({
+          name,
+          uri: "https://example.com",
+          maxChoicesPerVoter: 1,
+          choices: [
+            { name: "Aye", uri: null },
+            { name: "Nay", uri: null },
+          ],
+          tags: [],
+        })
+        .accountsStrict({
+          initializeProposalBase: {
+            payer: me,
+            guard,
+            guardUsage: null,
+            proposal,
+            owner: me,
+            proposalConfig,
+            organization,
+            systemProgram: SystemProgram.programId,
+            proposalProgram: PROPOSAL_PROGRAM_ID,
+            organizationProgram: anchor.workspace.Organization.programId,
+          },
+          proposer: me,
+        })
+        .rpc();
+
+      const proposalProgram = new anchor.Program(
+        PROPOSAL_IDL,
+        PROPOSAL_PROGRAM_ID
+      );
+
+      const account = await proposalProgram.account.proposalV0.fetch(proposal);
+
+      expect(account.name).to.eq(name);
+      expect(account.maxChoicesPerVoter).to.eq(1);
+      expect(account.choices.length).to.eq(2);
+    });
+  });
+
+  describe("with reputation guard", () => {
+    const reputationProgram = () =>
+      anchor.workspace.Reputation as anchor.Program<Reputation>;
+
+    const reputationManagerKey = (repConfig: PublicKey, wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("reputation_manager"),
+          repConfig.toBuffer(),
+          wallet.toBuffer(),
+        ],
+        anchor.workspace.Reputation.programId
+      );
+
+    const initializeReputationManager = async (
+      repConfig: PublicKey,
+      wallet: PublicKey
+    ) => {
+      const [reputationManager] = reputationManagerKey(repConfig, wallet);
+
+      await reputationProgram()
+        .methods.initializeReputationManagerV0({ namespace: repConfig })
+        .accountsStrict({
+          payer: me,
+          wallet,
+          repConfig,
+          reputationManager,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      return { reputationManager };
+    };
+
+    const context = async () => {
+      const name = "test" + Math.random();
+
+      const [repConfig] = PublicKey.findProgramAddressSync(
+        [Buffer.from("rep_config"), Buffer.from(name)],
+        anchor.workspace.Reputation.programId
+      );
+
+      await reputationProgram()
+        .methods.initializeRepVoterV0({
+          authority: me,
+          name,
+          votingRepReward: 1,
+          votingInAlignmentReward: 1,
+          proposalSuccessReward: 1,
+          tokenController: me,
+          tokenRequired: false,
+          proposalMinReputationGate: null,
+        })
+        .accountsStrict({
+          payer: me,
+          repConfig,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const { guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          reputation: {
+            repConfig,
+            minScore: new anchor.BN(0),
+          },
+        },
+      });
+
+      const { proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      });
+
+      const { organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      });
+
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      const initializeProposal = (reputationManager: PublicKey) =>
+        program.methods
+          .initializeProposalByReputationV0({
+            name,
+            uri: "https://example.com",
+            maxChoicesPerVoter: 1,
+            choices: [
+              { name: "Aye", uri: null },
+              { name: "Nay", uri: null },
+            ],
+            tags: [],
+          })
+          .accountsStrict({
+            initializeProposalBase: {
+              payer: me,
+              guard,
+              guardUsage: null,
+              proposal,
+              owner: me,
+              proposalConfig,
+              organization,
+              systemProgram: SystemProgram.programId,
+              proposalProgram: PROPOSAL_PROGRAM_ID,
+              organizationProgram: anchor.workspace.Organization.programId,
+            },
+            proposer: me,
+            repConfig,
+            reputationManager,
+          });
+
+      return { repConfig, proposal, initializeProposal };
+    };
+
+    it("initializes a reputation manager at its address", async () => {
+      const { repConfig } = await context();
+
+      const { reputationManager } = await initializeReputationManager(
+        repConfig,
+        me
+      );
+
+      const account =
+        await reputationProgram().account.reputationManagerV0.fetch(
+          reputationManager
+        );
+
+      expect(account.wallet.toBase58()).to.eq(me.toBase58());
+      expect(account.repuationConfig.toBase58()).to.eq(repConfig.toBase58());
+      expect(account.reputation.proposalVotes.toNumber()).to.eq(0);
+    });
+
+    it("initializes proposal with the proposer's reputation manager", async () => {
+      const { repConfig, proposal, initializeProposal } = await context();
+      const { reputationManager } = await initializeReputationManager(
+        repConfig,
+        me
+      );
+
+      await initializeProposal(reputationManager).rpc();
+
+      const proposalProgram = new anchor.Program(
+        PROPOSAL_IDL,
+        PROPOSAL_PROGRAM_ID
+      );
+
+      const account = await proposalProgram.account.proposalV0.fetch(proposal);
+
+      expect(account.choices.length).to.eq(2);
+    });
+
+    it("fails to initialize proposal with another wallet's reputation manager", async () => {
+      const { repConfig, initializeProposal } = await context();
+      const { reputationManager } = await initializeReputationManager(
+        repConfig,
+        Keypair.generate().publicKey
+      );
+
+      let logs: string;
+
+      try {
+        await initializeProposal(reputationManager).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/ConstraintSeeds/);
+    });
+  });
+
+  describe("with merkle wallets guard", () => {
+    const context = async () => {
+      const name = "test" + Math.random();
+
+      const tree = buildWalletTree([
+        ...Array.from({ length: 4 }, () => ({
+          wallet: Keypair.generate().publicKey,
+          multiplier: 1,
+        })),
+        { wallet: me, multiplier: 3 },
+      ]);
+
+      const { guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          merkleWallets: {
+            root: tree.root,
+          },
+        },
+        minWeight: new anchor.BN(3),
+      });
+
+      const { proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      });
+
+      const { organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      });
+
+      const buffer = Buffer.allocUnsafe(4);
+      buffer.writeUInt32LE(0); // num proposals
+      const [proposal] = proposalKey(organization, buffer);
+
+      const initializeProposal = (proofArgs) =>
+        program.methods
+          .initializeProposalByMerkleWalletV0(
+            {
+              name,
+              uri: "https://example.com",
+              maxChoicesPerVoter: 1,
+              choices: [
+                { name: "Aye", uri: null },
+                { name: "Nay", uri: null },
+              ],
+              tags: [],
+            },
+            proofArgs
+          )
+          .accountsStrict({
+            initializeProposalBase: {
+              payer: me,
+              guard,
+              guardUsage: null,
+              proposal,
+              owner: me,
+              proposalConfig,
+              organization,
+              systemProgram: SystemProgram.programId,
+              proposalProgram: PROPOSAL_PROGRAM_ID,
+              organizationProgram: anchor.workspace.Organization.programId,
+            },
+            proposer: me,
+          });
+
+      return { tree, proposal, initializeProposal };
+    };
+
+    it("initializes proposal with a proof of the wallet", async () => {
+      const { tree, proposal, initializeProposal } = await context();
+
+      await initializeProposal(tree.getProof(me)).rpc();
+
+      const proposalProgram = new anchor.Program(
+        PROPOSAL_IDL,
+        PROPOSAL_PROGRAM_ID
+      );
+
+      const account = await proposalProgram.account.proposalV0.fetch(proposal);
+
+      expect(account.choices.length).to.eq(2);
+    });
+
+    it("fails to initialize proposal with a forged multiplier", async () => {
+      const { tree, initializeProposal } = await context();
+
+      let logs: string;
+
+      try {
+        await initializeProposal({
+          ...tree.getProof(me),
+          multiplier: 10,
+        }).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/WalletProofVerificationFailed/);
+    });
+
+    it("fails to initialize proposal without a proof", async () => {
+      const { initializeProposal } = await context();
+
+      let logs: string;
+
+      try {
+        await initializeProposal({ multiplier: 3, proof: [] }).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/WalletProofVerificationFailed/);
+    });
+
+    it("fails to initialize guard nesting merkle wallets", async () => {
+      const { tree } = await context();
+
+      let logs: string;
+
+      try {
+        const name = "test" + Math.random();
+        const [nftGuard] = orgNftGuardKey(me, name);
+        await program.methods
+          .initializeGuardV0({
+            name,
+            guardType: {
+              anyOf: {
+                guards: [{ merkleWallets: { root: tree.root } }],
+              },
+            },
+            minWeight: new anchor.BN(0),
+            template: null,
+            limits: null,
+          })
+          .accountsStrict({
+            payer: me,
+            nftGuard,
+            authority: me,
+            systemProgram: SystemProgram.programId,
+          })
+          .simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/InvalidCompositeGuard/);
+    });
+  });
+
+  describe("with voting escrow guard", () => {
+    // Guards are owned by this program, so a mint list guard stands in for an
+    // escrow account: its authority is the owner, its only entry the mint and
+    // amount, and its min weight the lockup end. Names have a fixed length to
+    // keep the offsets fixed
+    const escrowNameLength = 14;
+    const escrowLayout = (discriminator: number[]) => ({
+      programId: program.programId,
+      discriminator,
+      ownerOffset: 8,
+      mintOffset: 8 + 32 + 4 + escrowNameLength + 1 + 4,
+      amountOffset: 8 + 32 + 4 + escrowNameLength + 1 + 4 + 32,
+      lockupEndOffset: 8 + 32 + 4 + escrowNameLength + 1 + 4 + 32 + 8,
+      delegateOffset: null,
+    });
+
+    const createEscrow = async ({
+      owner = me,
+      mint,
+      amount,
+      lockupEnd,
+      signers = [],
+    }) => {
+      const { guard: escrow } = await initializeGuard({
+        provider,
+        name: `escrow${random(10 ** 7, 10 ** 8 - 1)}`,
+        guardType: {
+          mintList: {
+            guardData: [{ address: mint, divisor: new anchor.BN(amount) }],
+          },
+        },
+        authority: owner,
+        minWeight: new anchor.BN(lockupEnd),
+        signers,
+      });
+
+      return escrow;
+    };
+
+    const now = () => Math.floor(new Date().valueOf() / 1000);
+
+    const context = async () => {
+      const name = "test" + Math.random();
+      const mint = Keypair.generate().publicKey;
+
+      const escrow = await createEscrow({
+        mint,
+        amount: 100,
+        lockupEnd: now() + 60 * 60 * 24,
+      });
+      const { data } = await provider.connection.getAccountInfo(escrow);
+
+      // The escrow's weight of 100 / 10 is needed to reach the min weight
+      const { guard } = await initializeGuard({
+        provider,
+        name,
+        guardType: {
+          votingEscrow: {
+            layout: escrowLayout([...data.subarray(0, 8)]),
+            guardData: [{ address: mint, divisor: new anchor.BN(10) }],
+          },
+        },
+        minWeight: new anchor.BN(10),
+      });
+
+      const { proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      });
+
+      const { organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      });
+
+      return { name, guard, proposalConfig, organization, mint, escrow };
+    };
+
+    it("initializes proposal with a locked escrow", async () => {
+      const { escrow, ...ctx } = await context();
+
+      await initializeProposalByProof({
+        ...ctx,
+        remainingAccounts: [escrow],
+      }).rpc();
+    });
+
+    it("fails to initialize proposal with an expired lockup", async () => {
+      const { escrow, mint, ...ctx } = await context();
+      const expired = await createEscrow({
+        mint,
+        amount: 100,
+        lockupEnd: now() - 100,
+      });
+
+      const logs = await simulateLogs(
+        initializeProposalByProof({ ...ctx, remainingAccounts: [expired] })
+      );
+
+      expect(logs).to.match(/EscrowLockupExpired/);
+    });
+
+    it("fails to initialize proposal with another wallet's escrow", async () => {
+      const { escrow, mint, ...ctx } = await context();
+      const owner = Keypair.generate();
+      const foreign = await createEscrow({
+        owner: owner.publicKey,
+        mint,
+        amount: 100,
+        lockupEnd: now() + 60 * 60 * 24,
+        signers: [owner],
+      });
+
+      const logs = await simulateLogs(
+        initializeProposalByProof({ ...ctx, remainingAccounts: [foreign] })
+      );
+
+      expect(logs).to.match(/InvalidProof/);
+    });
+
+    it("fails to initialize proposal passing the same escrow twice", async () => {
+      const { escrow, mint, ...ctx } = await context();
+
+      const logs = await simulateLogs(
+        initializeProposalByProof({
+          ...ctx,
+          remainingAccounts: [escrow, escrow],
+        })
+      );
+
+      expect(logs).to.match(/InvalidProof/);
+    });
+  });
+
+  describe("with composite guard", () => {
+    const context = async ({ guardType }) => {
+      const name = "test" + Math.random();
+
+      const { guard } = await initializeGuard({
+        provider,
+        name,
+        guardType,
+      });
+
+      const { proposalConfig } = await initalizeProposalConfig({
+        provider,
+        name,
+      });
+
+      const { organization } = await initializeOrganization({
+        provider,
+        name,
+        guard,
+        defaultProposalConfig: proposalConfig,
+      });
+
+      return { name, guard, proposalConfig, organization };
+    };
+
+    it("initializes proposal when any inner guard passes", async () => {
+      const [tokenAccount, mint] = await createMint(
+        provider.connection,
+        provider.wallet,
+        { amount: 10, decimals: 0, target: me }
+      );
+
+      const ctx = await context({
+        guardType: {
+          anyOf: {
+            guards: [
+              {
+                walletList: {
+                  guardData: [
+                    { address: Keypair.generate().publicKey, multiplier: 1 },
+                  ],
+                },
+              },
+              {
+                mintList: {
+                  guardData: [{ address: mint, divisor: new anchor.BN(1) }],
+                },
+              },
+            ],
+          },
+        },
+      });
+
+      await initializeProposalByProof({
+        ...ctx,
+        remainingAccounts: [tokenAccount],
+      }).rpc();
+    });
+
+    it("fails to initialize proposal when not all inner guards pass", async () => {
+      const [tokenAccount, mint] = await createMint(
+        provider.connection,
+        provider.wallet,
+        { amount: 10, decimals: 0, target: me }
+      );
+
+      const ctx = await context({
+        guardType: {
+          allOf: {
+            guards: [
+              {
+                walletList: {
+                  guardData: [
+                    { address: Keypair.generate().publicKey, multiplier: 1 },
+                  ],
+                },
+              },
+              {
+                mintList: {
+                  guardData: [{ address: mint, divisor: new anchor.BN(1) }],
+                },
+              },
+            ],
+          },
+        },
+      });
+
+      let logs: string;
+
+      try {
+        await initializeProposalByProof({
+          ...ctx,
+          remainingAccounts: [tokenAccount],
+        }).simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/ProposerNotValid/);
+    });
+
+    it("fails to initialize guard exceeding max depth", async () => {
+      let logs: string;
+
+      try {
+        const name = "test" + Math.random();
+        const [nftGuard] = orgNftGuardKey(me, name);
+        await program.methods
+          .initializeGuardV0({
+            name,
+            guardType: {
+              anyOf: {
+                guards: [
+                  { allOf: { guards: [{ anyOf: { guards: [{ permissive: {} }] } }] } },
+                ],
+              },
+            },
+            minWeight: new anchor.BN(0),
+            template: null,
+            limits: null,
+          })
+          .accountsStrict({
+            payer: me,
+            nftGuard,
+            authority: me,
+            systemProgram: SystemProgram.programId,
+          })
+          .simulate();
+      } catch (err) {
+        ({ logs } = err.simulationResponse || {});
+      }
+
+      expect(logs).to.match(/InvalidCompositeGuard/);
+    });
   });
 });
@@ -4,10 +4,13 @@ import { Proposal } from "../target/types/proposal";
 import { Organization } from "../target/types/organization";
 import { OrganizationWallet } from "../target/types/organization_wallet";
 import {
+  AddressLookupTableProgram,
   Keypair,
   PublicKey,
   SystemProgram,
   TransactionInstruction,
+  TransactionMessage,
+  VersionedTransaction,
 } from "@solana/web3.js";
 import {
   PROGRAM_ID as PROPOSAL_PID,
@@ -28,8 +31,10 @@ import { expect } from "chai";
 import { ensureIdls, makeid } from "./utils";
 import {
   getAssociatedTokenAddressSync,
+  createApproveInstruction,
   createAssociatedTokenAccountInstruction,
   createTransferInstruction,
+  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import {
   createAtaAndMint,
@@ -202,12 +207,1784 @@ describe("organization wallet", () => {
           .setTransactionsV0({
             choiceIndex: 0,
             transactionIndex: 0,
-            transaction,
-            allowExecutionOffset: 0,
+            transaction: {
+              ...transaction,
+              addressTableLookups: [],
+              actions: [],
+            },
             // Stop allowing execution after 1 week
-            disableExecutionOffset: 60 * 60 * 24 * 7,
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts(remainingAccounts)
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        await (
+          await executeTransaction({
+            program,
+            choiceTransaction: choiceTransaction!,
+          })
+        ).rpc({ skipPreflight: true });
+
+        const [executionReceipt] = PublicKey.findProgramAddressSync(
+          [Buffer.from("execution_receipt"), choiceTransaction!.toBuffer()],
+          PROGRAM_ID
+        );
+        const receipt = await program.account.executionReceiptV0.fetch(
+          executionReceipt
+        );
+        expect(receipt.executor.toBase58()).to.eq(me.toBase58());
+        expect(receipt.choiceIndex).to.eq(0);
+        expect(receipt.proposal.toBase58()).to.eq(proposal!.toBase58());
+
+        let logs: string | undefined;
+        try {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction: choiceTransaction!,
+            })
+          ).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+        expect(logs).to.match(/TransactionAlreadyExecuted/);
+      });
+
+      it("rejects executions exceeding the spending limits", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 500000000000,
+          }),
+        ]);
+
+        const mint = await createMint(provider, 0, me, me);
+        const lazySignerAta = await createAtaAndMint(
+          provider,
+          mint,
+          10,
+          wallet
+        );
+        const myAta = await createAtaAndMint(provider, mint, 0, me);
+
+        await program.methods
+          .updateOrganizationWalletV0({
+            name: null,
+            proposalConfigs: null,
+            spendingLimits: [
+              {
+                mint,
+                maxAmount: new anchor.BN(5),
+                window: { epoch: {} },
+              },
+            ],
+            allowedPrograms: null,
+            guardian: null,
+            proposalConfigPolicies: null,
+          })
+          .accounts({
+            organizationWallet,
+            organization,
+            authority: me,
+          })
+          .rpc({ skipPreflight: true });
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [createTransferInstruction(lazySignerAta, myAta, wallet, 10)],
+          []
+        );
+        const {
+          pubkeys: { choiceTransaction },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              ...transaction,
+              addressTableLookups: [],
+              actions: [],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts(remainingAccounts)
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        let logs: string | undefined;
+        try {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction: choiceTransaction!,
+            })
+          ).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/SpendingLimitExceeded/);
+      });
+
+      it("rejects executions delegating a limited wallet token account", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 500000000000,
+          }),
+        ]);
+
+        const mint = await createMint(provider, 0, me, me);
+        const lazySignerAta = await createAtaAndMint(
+          provider,
+          mint,
+          10,
+          wallet
+        );
+
+        await program.methods
+          .updateOrganizationWalletV0({
+            name: null,
+            proposalConfigs: null,
+            spendingLimits: [
+              {
+                mint,
+                maxAmount: new anchor.BN(5),
+                window: { epoch: {} },
+              },
+            ],
+            allowedPrograms: null,
+            guardian: null,
+            proposalConfigPolicies: null,
+          })
+          .accounts({
+            organizationWallet,
+            organization,
+            authority: me,
+          })
+          .rpc({ skipPreflight: true });
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          // Nothing moves yet, but the delegate could spend past the limit later
+          [createApproveInstruction(lazySignerAta, me, wallet, 10)],
+          []
+        );
+        const {
+          pubkeys: { choiceTransaction },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              ...transaction,
+              addressTableLookups: [],
+              actions: [],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts(remainingAccounts)
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        let logs: string | undefined;
+        try {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction: choiceTransaction!,
+            })
+          ).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/TokenAuthorityChanged/);
+      });
+
+      it("limits the lamports spent in a rolling window", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 1000000000,
+          }),
+        ]);
+        await program.methods
+          .updateOrganizationWalletV0({
+            name: null,
+            proposalConfigs: null,
+            spendingLimits: [
+              {
+                mint: PublicKey.default,
+                maxAmount: new anchor.BN(1500),
+                window: { rolling: { seconds: 60 * 60 * 24 } },
+              },
+            ],
+            allowedPrograms: null,
+            guardian: null,
+            proposalConfigPolicies: null,
+          })
+          .accounts({
+            organizationWallet,
+            organization,
+            authority: me,
+          })
+          .rpc({ skipPreflight: true });
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [
+            SystemProgram.transfer({
+              fromPubkey: wallet,
+              toPubkey: me,
+              lamports: 1000,
+            }),
+          ],
+          []
+        );
+        const choiceTransactions: PublicKey[] = [];
+        for (const transactionIndex of [0, 1]) {
+          const {
+            pubkeys: { choiceTransaction },
+          } = await program.methods
+            .setTransactionsV0({
+              choiceIndex: 0,
+              transactionIndex,
+              transaction: {
+                ...transaction,
+                addressTableLookups: [],
+                actions: [],
+              },
+              executionWindow: {
+                afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+              },
+              ordered: null,
+              chunked: null,
+            })
+            .remainingAccounts(remainingAccounts)
+            .accounts({
+              proposal,
+              organizationWallet,
+            })
+            .rpcAndKeys({ skipPreflight: true });
+          choiceTransactions.push(choiceTransaction!);
+        }
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        await (
+          await executeTransaction({
+            program,
+            choiceTransaction: choiceTransactions[0],
+          })
+        ).rpc({ skipPreflight: true });
+        const { spendingLimits } =
+          await program.account.organizationWalletV0.fetch(organizationWallet!);
+        expect(spendingLimits[0].spends).to.have.length(1);
+
+        let logs: string | undefined;
+        try {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction: choiceTransactions[1],
+            })
+          ).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/SpendingLimitExceeded/);
+      });
+
+      it("rejects executions assigning the wallet to another program", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 1000000000,
+          }),
+        ]);
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          // No lamports move, but the new owner could take them all later
+          [
+            SystemProgram.assign({
+              accountPubkey: wallet,
+              programId: TOKEN_PROGRAM_ID,
+            }),
+          ],
+          []
+        );
+        const {
+          pubkeys: { choiceTransaction },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              ...transaction,
+              addressTableLookups: [],
+              actions: [],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts(remainingAccounts)
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        let logs: string | undefined;
+        try {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction: choiceTransaction!,
+            })
+          ).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/WalletOwnerChanged/);
+      });
+
+      it("rejects transactions invoking programs outside the allowlist", async () => {
+        await program.methods
+          .updateOrganizationWalletV0({
+            name: null,
+            proposalConfigs: null,
+            spendingLimits: null,
+            allowedPrograms: [SystemProgram.programId],
+            guardian: null,
+            proposalConfigPolicies: null,
+          })
+          .accounts({
+            organizationWallet,
+            organization,
+            authority: me,
+          })
+          .rpc({ skipPreflight: true });
+
+        const wallet = walletKey(organization!, 0)[0];
+        const mint = await createMint(provider, 0, me, me);
+        const lazySignerAta = await createAtaAndMint(provider, mint, 10, wallet);
+        const myAta = await createAtaAndMint(provider, mint, 0, me);
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [createTransferInstruction(lazySignerAta, myAta, wallet, 10)],
+          []
+        );
+
+        let logs: string | undefined;
+        try {
+          await program.methods
+            .setTransactionsV0({
+              choiceIndex: 0,
+              transactionIndex: 0,
+              transaction: {
+                ...transaction,
+                addressTableLookups: [],
+                actions: [],
+              },
+              executionWindow: {
+                afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+              },
+              ordered: null,
+              chunked: null,
+            })
+            .remainingAccounts(remainingAccounts)
+            .accounts({
+              proposal,
+              organizationWallet,
+            })
+            .simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/ProgramNotAllowed/);
+      });
+
+      it("rejects transactions from signal only proposal configs", async () => {
+        await program.methods
+          .updateOrganizationWalletV0({
+            name: null,
+            proposalConfigs: null,
+            spendingLimits: null,
+            allowedPrograms: null,
+            guardian: null,
+            proposalConfigPolicies: [
+              {
+                proposalConfig: proposalConfig!,
+                signalOnly: true,
+                maxLamports: null,
+                minDelay: 0,
+                maxTokenAmounts: [],
+              },
+            ],
+          })
+          .accounts({
+            organizationWallet,
+            organization,
+            authority: me,
+          })
+          .rpc({ skipPreflight: true });
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [
+            SystemProgram.transfer({
+              fromPubkey: walletKey(organization!, 0)[0],
+              toPubkey: me,
+              lamports: 1,
+            }),
+          ],
+          []
+        );
+
+        let logs: string | undefined;
+        try {
+          await program.methods
+            .setTransactionsV0({
+              choiceIndex: 0,
+              transactionIndex: 0,
+              transaction: {
+                ...transaction,
+                addressTableLookups: [],
+                actions: [],
+              },
+              executionWindow: {
+                afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+              },
+              ordered: null,
+              chunked: null,
+            })
+            .remainingAccounts(remainingAccounts)
+            .accounts({
+              proposal,
+              organizationWallet,
+            })
+            .simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/PolicyViolation/);
+      });
+
+      it("rejects executions over the proposal config's lamport limit", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 1000000000,
+          }),
+        ]);
+        await program.methods
+          .updateOrganizationWalletV0({
+            name: null,
+            proposalConfigs: null,
+            spendingLimits: null,
+            allowedPrograms: null,
+            guardian: null,
+            proposalConfigPolicies: [
+              {
+                proposalConfig: proposalConfig!,
+                signalOnly: false,
+                maxLamports: new anchor.BN(1000),
+                minDelay: 0,
+                maxTokenAmounts: [],
+              },
+            ],
+          })
+          .accounts({
+            organizationWallet,
+            organization,
+            authority: me,
+          })
+          .rpc({ skipPreflight: true });
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [
+            SystemProgram.transfer({
+              fromPubkey: wallet,
+              toPubkey: me,
+              lamports: 1001,
+            }),
+          ],
+          []
+        );
+        const {
+          pubkeys: { choiceTransaction },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              ...transaction,
+              addressTableLookups: [],
+              actions: [],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts(remainingAccounts)
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        let logs: string | undefined;
+        try {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction: choiceTransaction!,
+            })
+          ).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/SpendingLimitExceeded/);
+      });
+
+      it("rejects executions over the proposal config's token cap", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        const mint = await createMint(provider, 0, me, me);
+        const walletAta = await createAtaAndMint(provider, mint, 10, wallet);
+        const myAta = await createAtaAndMint(provider, mint, 0, me);
+        await program.methods
+          .updateOrganizationWalletV0({
+            name: null,
+            proposalConfigs: null,
+            spendingLimits: null,
+            allowedPrograms: null,
+            guardian: null,
+            proposalConfigPolicies: [
+              {
+                proposalConfig: proposalConfig!,
+                signalOnly: false,
+                maxLamports: null,
+                minDelay: 0,
+                maxTokenAmounts: [{ mint, maxAmount: new anchor.BN(5) }],
+              },
+            ],
+          })
+          .accounts({
+            organizationWallet,
+            organization,
+            authority: me,
+          })
+          .rpc({ skipPreflight: true });
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [createTransferInstruction(walletAta, myAta, wallet, 6)],
+          []
+        );
+        const {
+          pubkeys: { choiceTransaction },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              ...transaction,
+              addressTableLookups: [],
+              actions: [],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts(remainingAccounts)
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        let logs: string | undefined;
+        try {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction: choiceTransaction!,
+            })
+          ).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/SpendingLimitExceeded/);
+      });
+
+      it("rejects duplicate policies for a proposal config", async () => {
+        const policy = {
+          proposalConfig: proposalConfig!,
+          signalOnly: false,
+          maxLamports: null,
+          minDelay: 0,
+          maxTokenAmounts: [],
+        };
+        let logs: string | undefined;
+        try {
+          await program.methods
+            .updateOrganizationWalletV0({
+              name: null,
+              proposalConfigs: null,
+              spendingLimits: null,
+              allowedPrograms: null,
+              guardian: null,
+              proposalConfigPolicies: [policy, { ...policy, signalOnly: true }],
+            })
+            .accounts({
+              organizationWallet,
+              organization,
+              authority: me,
+            })
+            .simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/InvalidProposalConfig/);
+      });
+
+      it("closes transactions of losing choices", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [
+            SystemProgram.transfer({
+              fromPubkey: wallet,
+              toPubkey: me,
+              lamports: 1,
+            }),
+          ],
+          []
+        );
+        const {
+          pubkeys: { choiceTransaction },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 1,
+            transactionIndex: 0,
+            transaction: {
+              ...transaction,
+              addressTableLookups: [],
+              actions: [],
+            },
+            executionWindow: {
+              // Never expires, but can be closed once its choice loses
+              afterResolution: { delay: 0, expiry: null },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts(remainingAccounts)
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        await program.methods
+          .closeExpiredTransactionV0()
+          .accounts({
+            payer: me,
+            proposal,
+            choiceTransaction,
+            executionReceipt: PublicKey.findProgramAddressSync(
+              [
+                Buffer.from("execution_receipt"),
+                choiceTransaction!.toBuffer(),
+              ],
+              PROGRAM_ID
+            )[0],
+          })
+          .rpc({ skipPreflight: true });
+
+        expect(
+          await program.account.choiceTransactionV0.fetchNullable(
+            choiceTransaction!
+          )
+        ).to.be.null;
+      });
+
+      it("keeps executed transactions open", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 1000000000,
+          }),
+        ]);
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [
+            SystemProgram.transfer({
+              fromPubkey: wallet,
+              toPubkey: me,
+              lamports: 1,
+            }),
+          ],
+          []
+        );
+        const {
+          pubkeys: { choiceTransaction },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              ...transaction,
+              addressTableLookups: [],
+              actions: [],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts(remainingAccounts)
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        await (
+          await executeTransaction({
+            program,
+            choiceTransaction: choiceTransaction!,
+          })
+        ).rpc({ skipPreflight: true });
+
+        // Cancelling expires every transaction of the proposal
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              cancelled: {},
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        let logs: string | undefined;
+        try {
+          await program.methods
+            .closeExpiredTransactionV0()
+            .accounts({
+              payer: me,
+              proposal,
+              choiceTransaction,
+              executionReceipt: PublicKey.findProgramAddressSync(
+                [
+                  Buffer.from("execution_receipt"),
+                  choiceTransaction!.toBuffer(),
+                ],
+                PROGRAM_ID
+              )[0],
+            })
+            .simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/TransactionAlreadyExecuted/);
+      });
+
+      it("executes ordered transactions in index order", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 1000000000,
+          }),
+        ]);
+
+        const choiceTransactions: PublicKey[] = [];
+        for (const transactionIndex of [0, 1]) {
+          const { transaction, remainingAccounts } = await compileTransaction(
+            [
+              SystemProgram.transfer({
+                fromPubkey: wallet,
+                toPubkey: me,
+                lamports: 1,
+              }),
+            ],
+            []
+          );
+          const {
+            pubkeys: { choiceTransaction },
+          } = await program.methods
+            .setTransactionsV0({
+              choiceIndex: 0,
+              transactionIndex,
+              transaction: {
+                ...transaction,
+                addressTableLookups: [],
+                actions: [],
+              },
+              executionWindow: {
+                afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+              },
+              ordered: true,
+              chunked: null,
+            })
+            .remainingAccounts(remainingAccounts)
+            .accounts({
+              proposal,
+              organizationWallet,
+            })
+            .rpcAndKeys({ skipPreflight: true });
+          choiceTransactions.push(choiceTransaction!);
+        }
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        let logs: string | undefined;
+        try {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction: choiceTransactions[1],
+            })
+          ).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+        expect(logs).to.match(/TransactionOutOfOrder/);
+
+        for (const choiceTransaction of choiceTransactions) {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction,
+            })
+          ).rpc({ skipPreflight: true });
+        }
+      });
+
+      it("executes transactions loading recipients from a lookup table", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 1000000000,
+          }),
+        ]);
+
+        const recipients = Array.from(
+          { length: 20 },
+          () => Keypair.generate().publicKey
+        );
+        const [createIx, lookupTable] =
+          AddressLookupTableProgram.createLookupTable({
+            authority: me,
+            payer: me,
+            recentSlot: await provider.connection.getSlot("finalized"),
+          });
+        await sendInstructions(provider, [
+          createIx,
+          AddressLookupTableProgram.extendLookupTable({
+            lookupTable,
+            authority: me,
+            payer: me,
+            addresses: recipients,
+          }),
+        ]);
+        // Tables can only be used the slot after they are extended
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+
+        // Static accounts are the wallet and the system program, recipients are loaded
+        const instructions = recipients.map((recipient, index) => ({
+          programIdIndex: 1,
+          accounts: Buffer.from([0, 2 + index]),
+          data: SystemProgram.transfer({
+            fromPubkey: wallet,
+            toPubkey: recipient,
+            lamports: 1000000,
+          }).data,
+        }));
+        const {
+          pubkeys: { choiceTransaction, walletProposal },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              numRwSigners: 1,
+              numRoSigners: 0,
+              numRw: 0,
+              instructions,
+              signerSeeds: [],
+              addressTableLookups: [
+                {
+                  table: lookupTable,
+                  writableIndexes: Buffer.from(recipients.map((_, i) => i)),
+                  readonlyIndexes: Buffer.from([]),
+                },
+              ],
+              actions: [],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts([
+            { pubkey: wallet, isWritable: true, isSigner: false },
+            {
+              pubkey: SystemProgram.programId,
+              isWritable: false,
+              isSigner: false,
+            },
+            { pubkey: lookupTable, isWritable: false, isSigner: false },
+          ])
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        const { transaction } =
+          await program.account.choiceTransactionV0.fetch(choiceTransaction);
+        expect(transaction.loadedWritable.map((k) => k.toBase58())).to.deep.eq(
+          recipients.map((k) => k.toBase58())
+        );
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        const { value: lookupTableAccount } =
+          await provider.connection.getAddressLookupTable(lookupTable);
+        const executeIx = await program.methods
+          .executeTransactionV0()
+          .accounts({
+            organizationWallet,
+            proposal,
+            choiceTransaction,
+            walletProposal,
+            wallet,
+            payer: me,
+          })
+          .remainingAccounts([
+            { pubkey: wallet, isWritable: true, isSigner: false },
+            {
+              pubkey: SystemProgram.programId,
+              isWritable: false,
+              isSigner: false,
+            },
+            ...recipients.map((pubkey) => ({
+              pubkey,
+              isWritable: true,
+              isSigner: false,
+            })),
+            { pubkey: lookupTable, isWritable: false, isSigner: false },
+          ])
+          .instruction();
+        // The outer transaction also loads the recipients from the table to fit
+        const message = new TransactionMessage({
+          payerKey: me,
+          recentBlockhash: (await provider.connection.getLatestBlockhash())
+            .blockhash,
+          instructions: [executeIx],
+        }).compileToV0Message([lookupTableAccount!]);
+        await provider.sendAndConfirm(new VersionedTransaction(message), [], {
+          skipPreflight: true,
+        });
+
+        for (const recipient of recipients) {
+          expect(await provider.connection.getBalance(recipient)).to.eq(
+            1000000
+          );
+        }
+      });
+
+      it("rejects lookups outside of the table or from deactivated tables", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        const [createIx, lookupTable] =
+          AddressLookupTableProgram.createLookupTable({
+            authority: me,
+            payer: me,
+            recentSlot: await provider.connection.getSlot("finalized"),
+          });
+        await sendInstructions(provider, [
+          createIx,
+          AddressLookupTableProgram.extendLookupTable({
+            lookupTable,
+            authority: me,
+            payer: me,
+            addresses: [Keypair.generate().publicKey],
+          }),
+        ]);
+
+        const setTransaction = (writableIndex: number) =>
+          program.methods
+            .setTransactionsV0({
+              choiceIndex: 0,
+              transactionIndex: 0,
+              transaction: {
+                numRwSigners: 1,
+                numRoSigners: 0,
+                numRw: 0,
+                instructions: [],
+                signerSeeds: [],
+                addressTableLookups: [
+                  {
+                    table: lookupTable,
+                    writableIndexes: Buffer.from([writableIndex]),
+                    readonlyIndexes: Buffer.from([]),
+                  },
+                ],
+                actions: [],
+              },
+              executionWindow: {
+                afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+              },
+              ordered: null,
+              chunked: null,
+            })
+            .remainingAccounts([
+              { pubkey: wallet, isWritable: true, isSigner: false },
+              { pubkey: lookupTable, isWritable: false, isSigner: false },
+            ])
+            .accounts({
+              proposal,
+              organizationWallet,
+            });
+
+        let logs: string | undefined;
+        try {
+          await setTransaction(1).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+        expect(logs).to.match(/InvalidLookupTable/);
+
+        await sendInstructions(provider, [
+          AddressLookupTableProgram.deactivateLookupTable({
+            lookupTable,
+            authority: me,
+          }),
+        ]);
+        logs = undefined;
+        try {
+          await setTransaction(0).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+        expect(logs).to.match(/LookupTableDeactivated/);
+      });
+
+      it("rejects instructions indexing past the transaction accounts", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        const recipient = Keypair.generate().publicKey;
+        const [createIx, lookupTable] =
+          AddressLookupTableProgram.createLookupTable({
+            authority: me,
+            payer: me,
+            recentSlot: await provider.connection.getSlot("finalized"),
+          });
+        await sendInstructions(provider, [
+          createIx,
+          AddressLookupTableProgram.extendLookupTable({
+            lookupTable,
+            authority: me,
+            payer: me,
+            addresses: [recipient],
+          }),
+        ]);
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+
+        // Keys are the wallet, the system program and the recipient, so index 3
+        // would be the lookup table passed after them
+        const {
+          pubkeys: { choiceTransaction, walletProposal },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              numRwSigners: 1,
+              numRoSigners: 0,
+              numRw: 0,
+              instructions: [
+                {
+                  programIdIndex: 1,
+                  accounts: Buffer.from([0, 3]),
+                  data: SystemProgram.transfer({
+                    fromPubkey: wallet,
+                    toPubkey: lookupTable,
+                    lamports: 1000000,
+                  }).data,
+                },
+              ],
+              signerSeeds: [],
+              addressTableLookups: [
+                {
+                  table: lookupTable,
+                  writableIndexes: Buffer.from([0]),
+                  readonlyIndexes: Buffer.from([]),
+                },
+              ],
+              actions: [],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts([
+            { pubkey: wallet, isWritable: true, isSigner: false },
+            {
+              pubkey: SystemProgram.programId,
+              isWritable: false,
+              isSigner: false,
+            },
+            { pubkey: lookupTable, isWritable: false, isSigner: false },
+          ])
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        let logs: string | undefined;
+        try {
+          await program.methods
+            .executeTransactionV0()
+            .accounts({
+              organizationWallet,
+              proposal,
+              choiceTransaction,
+              walletProposal,
+              wallet,
+              payer: me,
+            })
+            .remainingAccounts([
+              { pubkey: wallet, isWritable: true, isSigner: false },
+              {
+                pubkey: SystemProgram.programId,
+                isWritable: false,
+                isSigner: false,
+              },
+              { pubkey: recipient, isWritable: true, isSigner: false },
+              { pubkey: lookupTable, isWritable: true, isSigner: false },
+            ])
+            .simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+        expect(logs).to.match(/InvalidAccount/);
+      });
+
+      it("executes transactions uploaded in chunks", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 1000000000,
+          }),
+        ]);
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [
+            SystemProgram.transfer({
+              fromPubkey: wallet,
+              toPubkey: me,
+              lamports: 1000,
+            }),
+          ],
+          []
+        );
+        const [{ data, ...instruction }] = transaction.instructions;
+        const chunks = [data.subarray(0, 4), data.subarray(4)];
+
+        const {
+          pubkeys: { choiceTransaction },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              ...transaction,
+              instructions: [{ ...instruction, data: Buffer.from([]) }],
+              addressTableLookups: [],
+              actions: [],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: true,
+          })
+          .remainingAccounts(remainingAccounts)
+          .accounts({
+            proposal,
+            organizationWallet,
           })
-          .remainingAccounts(remainingAccounts)
+          .rpcAndKeys({ skipPreflight: true });
+
+        for (const chunk of chunks) {
+          await program.methods
+            .appendTransactionDataV0({
+              instructionIndex: 0,
+              data: Buffer.from(chunk),
+            })
+            .accounts({ proposal, choiceTransaction })
+            .rpc({ skipPreflight: true });
+        }
+        await program.methods
+          .finalizeTransactionV0()
+          .accounts({ proposal, choiceTransaction })
+          .rpc({ skipPreflight: true });
+
+        const acct = await program.account.choiceTransactionV0.fetch(
+          choiceTransaction!
+        );
+        expect(acct.finalized).to.be.true;
+        expect(Buffer.from(acct.transaction.instructions[0].data)).to.deep.eq(
+          Buffer.from(data)
+        );
+
+        let logs: string | undefined;
+        try {
+          await program.methods
+            .appendTransactionDataV0({
+              instructionIndex: 0,
+              data: Buffer.from([0]),
+            })
+            .accounts({ proposal, choiceTransaction })
+            .simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+        expect(logs).to.match(/TransactionFinalized/);
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        await (
+          await executeTransaction({
+            program,
+            choiceTransaction: choiceTransaction!,
+          })
+        ).rpc({ skipPreflight: true });
+      });
+
+      it("rejects execution windows that close before they open", async () => {
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [
+            SystemProgram.transfer({
+              fromPubkey: walletKey(organization!, 0)[0],
+              toPubkey: me,
+              lamports: 1,
+            }),
+          ],
+          []
+        );
+
+        let logs: string | undefined;
+        try {
+          await program.methods
+            .setTransactionsV0({
+              choiceIndex: 0,
+              transactionIndex: 0,
+              transaction: {
+                ...transaction,
+                addressTableLookups: [],
+                actions: [],
+              },
+              executionWindow: {
+                absolute: {
+                  from: new anchor.BN(2000),
+                  until: new anchor.BN(1000),
+                },
+              },
+              ordered: null,
+              chunked: null,
+            })
+            .remainingAccounts(remainingAccounts)
+            .accounts({
+              proposal,
+              organizationWallet,
+            })
+            .simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+
+        expect(logs).to.match(/InvalidExecutionWindow/);
+      });
+
+      it("allows the guardian to veto transactions during the delay", async () => {
+        await program.methods
+          .updateOrganizationWalletV0({
+            name: null,
+            proposalConfigs: null,
+            spendingLimits: null,
+            allowedPrograms: null,
+            guardian: me,
+            proposalConfigPolicies: null,
+          })
+          .accounts({
+            organizationWallet,
+            organization,
+            authority: me,
+          })
+          .rpc({ skipPreflight: true });
+
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: walletKey(organization!, 0)[0],
+            lamports: 1000000000,
+          }),
+        ]);
+
+        const { transaction, remainingAccounts } = await compileTransaction(
+          [
+            SystemProgram.transfer({
+              fromPubkey: walletKey(organization!, 0)[0],
+              toPubkey: me,
+              lamports: 1,
+            }),
+          ],
+          []
+        );
+        // The first transaction is delayed so it can be vetoed, the second is not
+        const choiceTransactions: PublicKey[] = [];
+        let walletProposal: PublicKey | undefined;
+        for (const delay of [60 * 60 * 24, 0]) {
+          const { pubkeys } = await program.methods
+            .setTransactionsV0({
+              choiceIndex: 0,
+              transactionIndex: choiceTransactions.length,
+              transaction: {
+                ...transaction,
+                addressTableLookups: [],
+                actions: [],
+              },
+              executionWindow: {
+                afterResolution: {
+                  delay,
+                  expiry: 60 * 60 * 24 * 7,
+                },
+              },
+              ordered: true,
+              chunked: null,
+            })
+            .remainingAccounts(remainingAccounts)
+            .accounts({
+              proposal,
+              organizationWallet,
+            })
+            .rpcAndKeys({ skipPreflight: true });
+          choiceTransactions.push(pubkeys.choiceTransaction!);
+          walletProposal = pubkeys.walletProposal;
+        }
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(Math.floor(new Date().valueOf() / 1000)),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        await program.methods
+          .vetoTransactionV0()
+          .accounts({
+            guardian: me,
+            organizationWallet,
+            proposal,
+            walletProposal,
+            choiceTransaction: choiceTransactions[0],
+          })
+          .rpc({ skipPreflight: true });
+
+        const vetoed = await program.account.choiceTransactionV0.fetch(
+          choiceTransactions[0]
+        );
+        expect(vetoed.vetoed).to.be.true;
+
+        // Ordered execution skips the vetoed transaction
+        await (
+          await executeTransaction({
+            program,
+            choiceTransaction: choiceTransactions[1],
+          })
+        ).rpc({ skipPreflight: true });
+
+        // Vetoed transactions can never execute, so their rent can be reclaimed
+        await program.methods
+          .closeExpiredTransactionV0()
+          .accounts({
+            payer: me,
+            proposal,
+            choiceTransaction: choiceTransactions[0],
+            executionReceipt: PublicKey.findProgramAddressSync(
+              [
+                Buffer.from("execution_receipt"),
+                choiceTransactions[0].toBuffer(),
+              ],
+              PROGRAM_ID
+            )[0],
+          })
+          .rpc({ skipPreflight: true });
+        expect(
+          await program.account.choiceTransactionV0.fetchNullable(
+            choiceTransactions[0]
+          )
+        ).to.be.null;
+      });
+
+      it("deposits lamports and tokens into the wallet", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        const balanceBefore = await provider.connection.getBalance(wallet);
+        await program.methods
+          .depositV0({ amount: new anchor.BN(1000000) })
+          .accounts({
+            depositor: me,
+            organizationWallet,
+            mint: null,
+            source: null,
+            destination: null,
+            tokenProgram: null,
+          })
+          .rpc({ skipPreflight: true });
+        expect(await provider.connection.getBalance(wallet)).to.eq(
+          balanceBefore + 1000000
+        );
+
+        const mint = await createMint(provider, 0, me, me);
+        const myAta = await createAtaAndMint(provider, mint, 10, me);
+        const walletAta = getAssociatedTokenAddressSync(mint, wallet, true);
+        await sendInstructions(provider, [
+          createAssociatedTokenAccountInstruction(me, walletAta, wallet, mint),
+        ]);
+        await program.methods
+          .depositV0({ amount: new anchor.BN(10) })
+          .accounts({
+            depositor: me,
+            organizationWallet,
+            mint,
+            source: myAta,
+            destination: walletAta,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc({ skipPreflight: true });
+        expect(
+          (await provider.connection.getTokenAccountBalance(walletAta)).value
+            .amount
+        ).to.eq("10");
+      });
+
+      it("executes typed transfer actions", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        const recipient = Keypair.generate().publicKey;
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 1000000000,
+          }),
+        ]);
+
+        const {
+          pubkeys: { choiceTransaction },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              numRwSigners: 1,
+              numRoSigners: 0,
+              numRw: 1,
+              instructions: [],
+              signerSeeds: [],
+              addressTableLookups: [],
+              actions: [
+                {
+                  transferV0: {
+                    mint: PublicKey.default,
+                    amount: new anchor.BN(1000000),
+                    programIndex: 2,
+                    sourceIndex: 0,
+                    destinationIndex: 1,
+                    mintIndex: 0,
+                  },
+                },
+              ],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts([
+            { pubkey: wallet, isWritable: true, isSigner: false },
+            { pubkey: recipient, isWritable: true, isSigner: false },
+            {
+              pubkey: SystemProgram.programId,
+              isWritable: false,
+              isSigner: false,
+            },
+          ])
           .accounts({
             proposal,
             organizationWallet,
@@ -236,6 +2013,255 @@ describe("organization wallet", () => {
             choiceTransaction: choiceTransaction!,
           })
         ).rpc({ skipPreflight: true });
+
+        expect(await provider.connection.getBalance(recipient)).to.eq(1000000);
+      });
+
+      it("only transfers tokens from the wallet's own token accounts", async () => {
+        const wallet = walletKey(organization!, 0)[0];
+        await sendInstructions(provider, [
+          SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: wallet,
+            lamports: 1000000000,
+          }),
+        ]);
+        const mint = await createMint(provider, 0, me, me);
+        const myAta = await createAtaAndMint(provider, mint, 10, me);
+        const walletAta = await createAtaAndMint(provider, mint, 10, wallet);
+        // The wallet may spend my tokens as a delegate, but actions must not
+        await sendInstructions(provider, [
+          createApproveInstruction(myAta, wallet, me, 10),
+        ]);
+
+        const {
+          pubkeys: { choiceTransaction: delegated },
+        } = await program.methods
+          .setTransactionsV0({
+            choiceIndex: 0,
+            transactionIndex: 0,
+            transaction: {
+              numRwSigners: 1,
+              numRoSigners: 0,
+              numRw: 2,
+              instructions: [],
+              signerSeeds: [],
+              addressTableLookups: [],
+              actions: [
+                {
+                  transferV0: {
+                    mint,
+                    amount: new anchor.BN(5),
+                    programIndex: 4,
+                    sourceIndex: 1,
+                    destinationIndex: 2,
+                    mintIndex: 3,
+                  },
+                },
+              ],
+            },
+            executionWindow: {
+              afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+            },
+            ordered: null,
+            chunked: null,
+          })
+          .remainingAccounts([
+            { pubkey: wallet, isWritable: true, isSigner: false },
+            { pubkey: myAta, isWritable: true, isSigner: false },
+            { pubkey: walletAta, isWritable: true, isSigner: false },
+            { pubkey: mint, isWritable: false, isSigner: false },
+            { pubkey: TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+          ])
+          .accounts({
+            proposal,
+            organizationWallet,
+          })
+          .rpcAndKeys({ skipPreflight: true });
+
+        await proposalProgram.methods
+          .updateStateV0({
+            newState: {
+              resolved: {
+                choices: [0],
+                endTs: new anchor.BN(
+                  Math.floor(new Date().valueOf() / 1000) - 100
+                ),
+              },
+            },
+          })
+          .accounts({
+            proposal,
+          })
+          .rpc({ skipPreflight: true });
+
+        let logs: string | undefined;
+        try {
+          await (
+            await executeTransaction({
+              program,
+              choiceTransaction: delegated!,
+            })
+          ).simulate();
+        } catch (err) {
+          logs = err.simulationResponse?.logs?.join("\n");
+        }
+        expect(logs).to.match(/InvalidAccount/);
+        expect(
+          (await provider.connection.getTokenAccountBalance(myAta)).value.amount
+        ).to.eq("10");
+      });
+
+      describe("while the proposal is draft", () => {
+        const setTransaction = async (
+          choiceIndex: number,
+          transactionIndex: number,
+          simulate = false
+        ) => {
+          const wallet = walletKey(organization!, 0)[0];
+          const { transaction, remainingAccounts } = await compileTransaction(
+            [
+              SystemProgram.transfer({
+                fromPubkey: wallet,
+                toPubkey: me,
+                lamports: 1,
+              }),
+            ],
+            []
+          );
+
+          const method = program.methods
+            .setTransactionsV0({
+              choiceIndex,
+              transactionIndex,
+              transaction: {
+                ...transaction,
+                addressTableLookups: [],
+                actions: [],
+              },
+              executionWindow: {
+                afterResolution: { delay: 0, expiry: 60 * 60 * 24 * 7 },
+              },
+              ordered: null,
+              chunked: null,
+            })
+            .remainingAccounts(remainingAccounts)
+            .accounts({
+              proposal,
+              organizationWallet,
+            });
+          if (simulate) {
+            await method.simulate();
+          }
+          const { pubkeys } = await method.rpcAndKeys({ skipPreflight: true });
+
+          return pubkeys;
+        };
+
+        it("counts multiple transactions per choice", async () => {
+          await setTransaction(0, 0);
+          await setTransaction(0, 1);
+          const { walletProposal } = await setTransaction(1, 0);
+
+          const acct = await program.account.walletProposalV0.fetch(
+            walletProposal!
+          );
+          expect(acct.numTransactionsByChoice).to.deep.eq([2, 1]);
+        });
+
+        it("rejects gaps and duplicate transaction indices", async () => {
+          await setTransaction(0, 0);
+
+          for (const transactionIndex of [0, 2]) {
+            let logs: string | undefined;
+            try {
+              await setTransaction(0, transactionIndex, true);
+            } catch (err) {
+              logs = err.simulationResponse?.logs?.join("\n");
+            }
+            expect(logs).to.match(/InvalidTransactionIndex/);
+          }
+        });
+
+        it("removes the last transaction of a choice", async () => {
+          await setTransaction(0, 0);
+          const { walletProposal, choiceTransaction } = await setTransaction(
+            0,
+            1
+          );
+
+          await program.methods
+            .removeTransactionV0({ choiceIndex: 0, transactionIndex: 1 })
+            .accounts({
+              payer: me,
+              proposal,
+              walletProposal,
+              choiceTransaction,
+            })
+            .rpc({ skipPreflight: true });
+
+          expect(
+            await program.account.choiceTransactionV0.fetchNullable(
+              choiceTransaction!
+            )
+          ).to.be.null;
+        });
+
+        it("only refunds the rent to the payer of the transaction", async () => {
+          await setTransaction(0, 0);
+          const { walletProposal, choiceTransaction } = await setTransaction(
+            0,
+            1
+          );
+
+          let logs: string | undefined;
+          try {
+            await program.methods
+              .removeTransactionV0({ choiceIndex: 0, transactionIndex: 1 })
+              .accounts({
+                payer: Keypair.generate().publicKey,
+                proposal,
+                walletProposal,
+                choiceTransaction,
+              })
+              .simulate();
+          } catch (err) {
+            logs = err.simulationResponse?.logs?.join("\n");
+          }
+          expect(logs).to.match(/ConstraintHasOne/);
+        });
+
+        it("clears the transactions of a choice", async () => {
+          const transactions = [
+            await setTransaction(0, 0),
+            await setTransaction(0, 1),
+          ];
+          const walletProposal = transactions[0].walletProposal;
+
+          await program.methods
+            .clearChoiceTransactionsV0({ choiceIndex: 0 })
+            .accounts({
+              proposal,
+              walletProposal,
+            })
+            .remainingAccounts([
+              ...transactions.map(({ choiceTransaction }) => ({
+                pubkey: choiceTransaction!,
+                isWritable: true,
+                isSigner: false,
+              })),
+              { pubkey: me, isWritable: true, isSigner: false },
+            ])
+            .rpc({ skipPreflight: true });
+
+          for (const { choiceTransaction } of transactions) {
+            expect(
+              await program.account.choiceTransactionV0.fetchNullable(
+                choiceTransaction!
+              )
+            ).to.be.null;
+          }
+        });
       });
 
       describe("update_organization_wallet_v0", () => {
@@ -262,6 +2288,10 @@ describe("organization wallet", () => {
             .updateOrganizationWalletV0({
               name: otherName,
               proposalConfigs: null,
+              spendingLimits: null,
+              allowedPrograms: null,
+              guardian: null,
+              proposalConfigPolicies: null,
             })
             .accounts({
               organizationWallet,
@@ -285,6 +2315,10 @@ describe("organization wallet", () => {
             .updateOrganizationWalletV0({
               name: null,
               proposalConfigs: [otherProposalConfig!],
+              spendingLimits: null,
+              allowedPrograms: null,
+              guardian: null,
+              proposalConfigPolicies: null,
             })
             .accounts({
               organizationWallet,
@@ -327,6 +2361,56 @@ describe("organization wallet", () => {
           expect(acct.proposalConfigs).to.have.length(proposalConfigs.length);
         });
 
+        it("should refund rent to the payer when proposal configs shrink", async () => {
+          const payer = Keypair.generate();
+          await sendInstructions(provider, [
+            SystemProgram.transfer({
+              fromPubkey: me,
+              toPubkey: payer.publicKey,
+              lamports: 1000000000,
+            }),
+          ]);
+          const update = (proposalConfigs: PublicKey[]) =>
+            program.methods
+              .updateOrganizationWalletV0({
+                name: null,
+                proposalConfigs,
+              })
+              .accounts({
+                payer: payer.publicKey,
+                organizationWallet,
+                organization,
+                authority: me,
+              })
+              .signers([payer])
+              .rpc({ skipPreflight: true });
+          await update(
+            Array.from({ length: 20 }).map(() => Keypair.generate().publicKey)
+          );
+          const grown = await provider.connection.getAccountInfo(
+            organizationWallet!
+          );
+          const payerBalance = await provider.connection.getBalance(
+            payer.publicKey
+          );
+
+          await update([proposalConfig!]);
+          const shrunk = await provider.connection.getAccountInfo(
+            organizationWallet!
+          );
+
+          expect(shrunk!.data.length).to.be.lessThan(grown!.data.length);
+          expect(shrunk!.lamports).to.eq(
+            await provider.connection.getMinimumBalanceForRentExemption(
+              shrunk!.data.length
+            )
+          );
+          // The provider pays the fees, so the payer only receives the excess rent
+          expect(await provider.connection.getBalance(payer.publicKey)).to.eq(
+            payerBalance + grown!.lamports - shrunk!.lamports
+          );
+        });
+
         it("should fail if not the authority", async () => {
           const authority = Keypair.generate().publicKey;
 
@@ -348,6 +2432,10 @@ describe("organization wallet", () => {
               .updateOrganizationWalletV0({
                 name: otherName,
                 proposalConfigs: [otherProposalConfig!],
+                spendingLimits: null,
+                allowedPrograms: null,
+                guardian: null,
+                proposalConfigPolicies: null,
               })
               .accounts({
                 organizationWallet,
@@ -380,6 +2468,10 @@ describe("organization wallet", () => {
               .updateOrganizationWalletV0({
                 name: otherName,
                 proposalConfigs: [otherProposalConfig!],
+                spendingLimits: null,
+                allowedPrograms: null,
+                guardian: null,
+                proposalConfigPolicies: null,
               })
               .accounts({
                 organizationWallet,
@@ -277,15 +277,16 @@ describe("organization", () => {
 
        guardKey = PublicKey.findProgramAddressSync([
                 Buffer.from("guard"),
+                me.toBuffer(),
                 Buffer.from(guardName)
               ], gaurdPID)[0]
         await guardProgram.methods.initializeGuardV0({
           name: guardName,
           guardType: {collectionMint: {mints: [collection]}},
-          authority: me
         }).accountsStrict({
           payer: me,
           nftGuard: guardKey,
+          authority: me,
           systemProgram: anchor.web3.SystemProgram.programId
         }).rpc({skipPreflight: true})
 
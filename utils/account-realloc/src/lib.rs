@@ -0,0 +1,104 @@
+//! Resizes program owned accounts while keeping them exactly rent exempt, so that rent
+//! is topped up when they grow and refunded when they shrink.
+
+use anchor_lang::{
+    error::Error,
+    prelude::*,
+    solana_program::{
+        entrypoint::MAX_PERMITTED_DATA_INCREASE, program::invoke, system_instruction,
+    },
+};
+use std::io::Write;
+
+struct SizeWriter {
+    total: usize,
+}
+
+impl Write for SizeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.total += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Size of the account once serialized, including its discriminator
+pub fn serialized_size<T: AccountSerialize>(account: &T) -> Result<usize> {
+    let writer = &mut SizeWriter { total: 0 };
+    account.try_serialize(writer)?;
+    Ok(writer.total)
+}
+
+/// Resizes `account` towards `new_size`, taking missing rent from `payer` and refunding
+/// excess rent to it. An account can only grow by `MAX_PERMITTED_DATA_INCREASE` past the
+/// size it had when the instruction started (0 for accounts created in it), so larger
+/// growth stops there and continues in later instructions. Returns the size reached
+pub fn realloc_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_size: usize,
+) -> Result<usize> {
+    let size = new_size.min(account.original_data_len() + MAX_PERMITTED_DATA_INCREASE);
+    let minimum_balance = Rent::get()?.minimum_balance(size);
+    let lamports = account.lamports();
+    if minimum_balance > lamports {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, minimum_balance - lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    } else if lamports > minimum_balance {
+        // The program owns the account, so it can debit it directly
+        **account.try_borrow_mut_lamports()? -= lamports - minimum_balance;
+        **payer.try_borrow_mut_lamports()? += lamports - minimum_balance;
+    }
+
+    if size != account.data_len() {
+        msg!("Resizing to {}", size);
+        account.realloc(size, false)?;
+    }
+
+    Ok(size)
+}
+
+/// Resizes the account to exactly fit its data, see `realloc_account`. Data that can't fit
+/// within a single instruction fails with `data_increase_error`
+pub fn resize_to_fit<'info, T: AccountSerialize + AccountDeserialize + Owner + Clone>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    account: &Account<'info, T>,
+    data_increase_error: impl Into<Error>,
+) -> Result<()> {
+    let new_size = serialized_size(&**account)?;
+    let size = realloc_account(&account.to_account_info(), payer, system_program, new_size)?;
+    if size != new_size {
+        return Err(data_increase_error.into());
+    }
+
+    Ok(())
+}
+
+/// Grows the account to fit its data, leaving it as is when it already fits. Use this when
+/// `payer` did not pay for the account, so it can't collect the rent of any padding
+pub fn grow_to_fit<'info, T: AccountSerialize + AccountDeserialize + Owner + Clone>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    account: &Account<'info, T>,
+    data_increase_error: impl Into<Error>,
+) -> Result<()> {
+    let new_size = serialized_size(&**account)?;
+    let account = account.to_account_info();
+    if new_size <= account.data_len() {
+        return Ok(());
+    }
+
+    let size = realloc_account(&account, payer, system_program, new_size)?;
+    if size != new_size {
+        return Err(data_increase_error.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,535 @@
+use crate::{effect::Effect, error::Error, snapshot::Snapshot};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{system_instruction::SystemInstruction, system_program},
+    Discriminator,
+};
+use anchor_spl::{associated_token, token::spl_token::instruction::TokenInstruction, token_2022};
+use organization_wallet::state::{
+    ActionV0, ChoiceTransactionV0, CompiledInstructionV0, CompiledTransactionV0,
+};
+
+type DecodeResult<T> = std::result::Result<T, Error>;
+
+/// Keys of the transaction, followed by the accounts it loads from lookup tables, in the
+/// order instruction indices refer to them. Loaded accounts are the ones resolved when the
+/// transaction was set, which are the ones it executes with
+pub fn transaction_keys(transaction: &CompiledTransactionV0) -> Vec<Pubkey> {
+    transaction
+        .accounts
+        .iter()
+        .chain(transaction.loaded_writable.iter())
+        .chain(transaction.loaded_readonly.iter())
+        .copied()
+        .collect()
+}
+
+/// Checks that a finalized transaction matches the hash stored next to it, so the decoded
+/// payload is the one voters approve
+pub fn verify_transaction_hash(
+    key: &Pubkey,
+    choice_transaction: &ChoiceTransactionV0,
+) -> DecodeResult<()> {
+    if !choice_transaction.finalized {
+        return Ok(());
+    }
+
+    match choice_transaction.transaction.hash() {
+        Ok(hash) if hash == choice_transaction.transaction_hash => Ok(()),
+        _ => Err(Error::HashMismatch(*key)),
+    }
+}
+
+/// Effects of the transaction's instructions, followed by those of its typed actions
+pub fn decode_transaction(
+    transaction: &CompiledTransactionV0,
+    snapshot: &Snapshot,
+) -> DecodeResult<Vec<Effect>> {
+    let keys = transaction_keys(transaction);
+    let key = |index: u8| {
+        keys.get(index as usize)
+            .copied()
+            .ok_or(Error::InvalidIndex(index))
+    };
+
+    let mut effects = vec![];
+    for ix in &transaction.instructions {
+        effects.push(decode_compiled_instruction(ix, &key, snapshot)?);
+    }
+    for action in &transaction.actions {
+        effects.push(decode_action(action, &key, snapshot)?);
+    }
+
+    Ok(effects)
+}
+
+fn decode_compiled_instruction(
+    ix: &CompiledInstructionV0,
+    key: &impl Fn(u8) -> DecodeResult<Pubkey>,
+    snapshot: &Snapshot,
+) -> DecodeResult<Effect> {
+    let program_id = key(ix.program_id_index)?;
+    let accounts = ix
+        .accounts
+        .iter()
+        .map(|index| key(*index))
+        .collect::<DecodeResult<Vec<_>>>()?;
+
+    Ok(decode_instruction(
+        &program_id,
+        &accounts,
+        &ix.data,
+        snapshot,
+    ))
+}
+
+fn decode_action(
+    action: &ActionV0,
+    key: &impl Fn(u8) -> DecodeResult<Pubkey>,
+    snapshot: &Snapshot,
+) -> DecodeResult<Effect> {
+    match *action {
+        ActionV0::TransferV0 {
+            mint,
+            amount,
+            source_index,
+            destination_index,
+            ..
+        } => {
+            let source = key(source_index)?;
+            let destination = key(destination_index)?;
+            if mint == Pubkey::default() {
+                return Ok(Effect::TransferLamports {
+                    from: source,
+                    to: destination,
+                    lamports: amount,
+                });
+            }
+
+            Ok(Effect::TransferTokens {
+                source,
+                destination,
+                authority: snapshot.token_account(&source).map(|account| account.owner),
+                mint: Some(mint),
+                amount,
+                decimals: snapshot.mint_decimals(&mint),
+            })
+        }
+    }
+}
+
+/// Summarizes an instruction of a known program. Falls back to `Effect::Unknown` for
+/// unknown programs or data that does not decode
+pub fn decode_instruction(
+    program_id: &Pubkey,
+    accounts: &[Pubkey],
+    data: &[u8],
+    snapshot: &Snapshot,
+) -> Effect {
+    let effect = if *program_id == system_program::ID {
+        decode_system(accounts, data)
+    } else if *program_id == anchor_spl::token::ID || *program_id == token_2022::ID {
+        decode_token(accounts, data, snapshot)
+    } else if *program_id == associated_token::ID {
+        decode_associated_token(accounts, data)
+    } else if *program_id == organization::ID {
+        decode_anchor("Organization", ORGANIZATION_INSTRUCTIONS, accounts, data)
+    } else if *program_id == organization_wallet::ID {
+        decode_anchor(
+            "Organization Wallet",
+            ORGANIZATION_WALLET_INSTRUCTIONS,
+            accounts,
+            data,
+        )
+    } else if *program_id == org_nft_guard::ID {
+        decode_anchor("Org Guard", ORG_GUARD_INSTRUCTIONS, accounts, data)
+    } else {
+        None
+    };
+
+    effect.unwrap_or_else(|| Effect::Unknown {
+        program_id: *program_id,
+        accounts: accounts.to_vec(),
+        data: data.to_vec(),
+    })
+}
+
+fn decode_system(accounts: &[Pubkey], data: &[u8]) -> Option<Effect> {
+    let instruction: SystemInstruction = bincode::deserialize(data).ok()?;
+    Some(match instruction {
+        SystemInstruction::Transfer { lamports } => Effect::TransferLamports {
+            from: *accounts.first()?,
+            to: *accounts.get(1)?,
+            lamports,
+        },
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => Effect::CreateAccount {
+            payer: *accounts.first()?,
+            account: *accounts.get(1)?,
+            owner,
+            lamports,
+            space,
+        },
+        other => Effect::Instruction {
+            program: "System",
+            instruction: format!("{other:?}"),
+            accounts: accounts.to_vec(),
+            details: vec![],
+        },
+    })
+}
+
+fn decode_token(accounts: &[Pubkey], data: &[u8], snapshot: &Snapshot) -> Option<Effect> {
+    let account = |index: usize| accounts.get(index).copied();
+    let instruction = TokenInstruction::unpack(data).ok()?;
+    Some(match instruction {
+        TokenInstruction::Transfer { amount } => {
+            let source = account(0)?;
+            let mint = snapshot.token_account(&source).map(|source| source.mint);
+            Effect::TransferTokens {
+                source,
+                destination: account(1)?,
+                authority: account(2),
+                mint,
+                amount,
+                decimals: mint.and_then(|mint| snapshot.mint_decimals(&mint)),
+            }
+        }
+        TokenInstruction::TransferChecked { amount, decimals } => Effect::TransferTokens {
+            source: account(0)?,
+            mint: account(1),
+            destination: account(2)?,
+            authority: account(3),
+            amount,
+            decimals: Some(decimals),
+        },
+        TokenInstruction::MintTo { amount } => {
+            let mint = account(0)?;
+            Effect::MintTokens {
+                mint,
+                destination: account(1)?,
+                authority: account(2)?,
+                amount,
+                decimals: snapshot.mint_decimals(&mint),
+            }
+        }
+        TokenInstruction::MintToChecked { amount, decimals } => Effect::MintTokens {
+            mint: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+            amount,
+            decimals: Some(decimals),
+        },
+        TokenInstruction::Burn { amount } => {
+            let mint = account(1)?;
+            Effect::BurnTokens {
+                account: account(0)?,
+                mint,
+                authority: account(2)?,
+                amount,
+                decimals: snapshot.mint_decimals(&mint),
+            }
+        }
+        TokenInstruction::BurnChecked { amount, decimals } => Effect::BurnTokens {
+            account: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+            amount,
+            decimals: Some(decimals),
+        },
+        TokenInstruction::Approve { amount } => {
+            let source = account(0)?;
+            let mint = snapshot.token_account(&source).map(|source| source.mint);
+            Effect::ApproveTokens {
+                account: source,
+                delegate: account(1)?,
+                owner: account(2)?,
+                mint,
+                amount,
+                decimals: mint.and_then(|mint| snapshot.mint_decimals(&mint)),
+            }
+        }
+        TokenInstruction::ApproveChecked { amount, decimals } => Effect::ApproveTokens {
+            account: account(0)?,
+            mint: account(1),
+            delegate: account(2)?,
+            owner: account(3)?,
+            amount,
+            decimals: Some(decimals),
+        },
+        TokenInstruction::Revoke => Effect::RevokeTokens {
+            account: account(0)?,
+            owner: account(1)?,
+        },
+        TokenInstruction::CloseAccount => Effect::CloseTokenAccount {
+            account: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+        },
+        TokenInstruction::SetAuthority {
+            authority_type,
+            new_authority,
+        } => Effect::SetTokenAuthority {
+            account: account(0)?,
+            authority_type: format!("{authority_type:?}"),
+            new_authority: new_authority.into(),
+        },
+        other => Effect::Instruction {
+            program: "Token",
+            instruction: format!("{other:?}"),
+            accounts: accounts.to_vec(),
+            details: vec![],
+        },
+    })
+}
+
+fn decode_associated_token(accounts: &[Pubkey], data: &[u8]) -> Option<Effect> {
+    match data.first() {
+        // Create and CreateIdempotent
+        None | Some(0) | Some(1) => Some(Effect::CreateAssociatedTokenAccount {
+            payer: *accounts.first()?,
+            account: *accounts.get(1)?,
+            wallet: *accounts.get(2)?,
+            mint: *accounts.get(3)?,
+        }),
+        Some(2) => Some(Effect::Instruction {
+            program: "Associated Token",
+            instruction: "RecoverNested".to_string(),
+            accounts: accounts.to_vec(),
+            details: vec![],
+        }),
+        _ => None,
+    }
+}
+
+type InstructionTable = &'static [([u8; 8], &'static str)];
+
+const ORGANIZATION_INSTRUCTIONS: InstructionTable = &[
+    (
+        organization::instruction::InitializeOrganizationV0::DISCRIMINATOR,
+        "initialize_organization_v0",
+    ),
+    (
+        organization::instruction::InitializeProposalV0::DISCRIMINATOR,
+        "initialize_proposal_v0",
+    ),
+    (
+        organization::instruction::UpdateOrganizationV0::DISCRIMINATOR,
+        "update_organization_v0",
+    ),
+];
+
+const ORGANIZATION_WALLET_INSTRUCTIONS: InstructionTable = &[
+    (
+        organization_wallet::instruction::InitializeOrganizationWalletV0::DISCRIMINATOR,
+        "initialize_organization_wallet_v0",
+    ),
+    (
+        organization_wallet::instruction::SetTransactionsV0::DISCRIMINATOR,
+        "set_transactions_v0",
+    ),
+    (
+        organization_wallet::instruction::AppendTransactionDataV0::DISCRIMINATOR,
+        "append_transaction_data_v0",
+    ),
+    (
+        organization_wallet::instruction::FinalizeTransactionV0::DISCRIMINATOR,
+        "finalize_transaction_v0",
+    ),
+    (
+        organization_wallet::instruction::RemoveTransactionV0::DISCRIMINATOR,
+        "remove_transaction_v0",
+    ),
+    (
+        organization_wallet::instruction::ClearChoiceTransactionsV0::DISCRIMINATOR,
+        "clear_choice_transactions_v0",
+    ),
+    (
+        organization_wallet::instruction::DepositV0::DISCRIMINATOR,
+        "deposit_v0",
+    ),
+    (
+        organization_wallet::instruction::ExecuteTransactionV0::DISCRIMINATOR,
+        "execute_transaction_v0",
+    ),
+    (
+        organization_wallet::instruction::CloseExpiredTransactionV0::DISCRIMINATOR,
+        "close_expired_transaction_v0",
+    ),
+    (
+        organization_wallet::instruction::VetoTransactionV0::DISCRIMINATOR,
+        "veto_transaction_v0",
+    ),
+    (
+        organization_wallet::instruction::MigrateChoiceTransactionV0::DISCRIMINATOR,
+        "migrate_choice_transaction_v0",
+    ),
+    (
+        organization_wallet::instruction::UpdateOrganizationWalletV0::DISCRIMINATOR,
+        "update_organization_wallet_v0",
+    ),
+];
+
+const ORG_GUARD_INSTRUCTIONS: InstructionTable = &[
+    (
+        org_nft_guard::instruction::InitializeGuardV0::DISCRIMINATOR,
+        "initialize_guard_v0",
+    ),
+    (
+        org_nft_guard::instruction::InitializeGuardUsageV0::DISCRIMINATOR,
+        "initialize_guard_usage_v0",
+    ),
+    (
+        org_nft_guard::instruction::UpdateGuardV0::DISCRIMINATOR,
+        "update_guard_v0",
+    ),
+    (
+        org_nft_guard::instruction::CloseGuardV0::DISCRIMINATOR,
+        "close_guard_v0",
+    ),
+    (
+        org_nft_guard::instruction::InitializeProposalByCompressedNftV0::DISCRIMINATOR,
+        "initialize_proposal_by_compressed_nft_v0",
+    ),
+    (
+        org_nft_guard::instruction::InitializeProposalByNftV0::DISCRIMINATOR,
+        "initialize_proposal_by_nft_v0",
+    ),
+    (
+        org_nft_guard::instruction::InitializeProposalByTokenV0::DISCRIMINATOR,
+        "initialize_proposal_by_token_v0",
+    ),
+    (
+        org_nft_guard::instruction::InitializeProposalByWalletV0::DISCRIMINATOR,
+        "initialize_proposal_by_wallet_v0",
+    ),
+    (
+        org_nft_guard::instruction::InitializeProposalByMerkleWalletV0::DISCRIMINATOR,
+        "initialize_proposal_by_merkle_wallet_v0",
+    ),
+    (
+        org_nft_guard::instruction::InitializeProposalByProofV0::DISCRIMINATOR,
+        "initialize_proposal_by_proof_v0",
+    ),
+    (
+        org_nft_guard::instruction::InitializeProposalByReputationV0::DISCRIMINATOR,
+        "initialize_proposal_by_reputation_v0",
+    ),
+    (
+        org_nft_guard::instruction::InitializeProposalPermissivelyV0::DISCRIMINATOR,
+        "initialize_proposal_permissively_v0",
+    ),
+];
+
+fn decode_anchor(
+    program: &'static str,
+    instructions: InstructionTable,
+    accounts: &[Pubkey],
+    data: &[u8],
+) -> Option<Effect> {
+    let (discriminator, mut args) = (data.get(..8)?, data.get(8..)?);
+    let (_, name) = instructions
+        .iter()
+        .find(|(known, _)| known == discriminator)?;
+
+    Some(Effect::Instruction {
+        program,
+        instruction: name.to_string(),
+        accounts: accounts.to_vec(),
+        details: anchor_details(discriminator, &mut args).unwrap_or_default(),
+    })
+}
+
+fn format_keys(keys: &[Pubkey]) -> String {
+    keys.iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Arguments of the instructions that change who controls an organization or its wallets
+fn anchor_details(discriminator: &[u8], args: &mut &[u8]) -> Option<Vec<(&'static str, String)>> {
+    let mut details = vec![];
+    if discriminator == organization::instruction::UpdateOrganizationV0::DISCRIMINATOR {
+        let args = organization::instruction::UpdateOrganizationV0::deserialize(args)
+            .ok()?
+            .args;
+        let keys = [
+            ("authority", args.authority),
+            ("default_proposal_config", args.default_proposal_config),
+            ("proposal_program", args.proposal_program),
+            ("guard", args.guard),
+        ];
+        for (name, key) in keys {
+            if let Some(key) = key {
+                details.push((name, key.to_string()));
+            }
+        }
+        if let Some(uri) = args.uri {
+            details.push(("uri", uri));
+        }
+    } else if discriminator
+        == organization_wallet::instruction::UpdateOrganizationWalletV0::DISCRIMINATOR
+    {
+        let args = organization_wallet::instruction::UpdateOrganizationWalletV0::deserialize(args)
+            .ok()?
+            .args;
+        if let Some(name) = args.name {
+            details.push(("name", name));
+        }
+        if let Some(proposal_configs) = args.proposal_configs {
+            details.push(("proposal_configs", format_keys(&proposal_configs)));
+        }
+        if let Some(spending_limits) = args.spending_limits {
+            let limits = spending_limits
+                .iter()
+                .map(|limit| format!("{} of {}", limit.max_amount, limit.mint))
+                .collect::<Vec<_>>();
+            details.push(("spending_limits", limits.join(", ")));
+        }
+        if let Some(allowed_programs) = args.allowed_programs {
+            details.push(("allowed_programs", format_keys(&allowed_programs)));
+        }
+        if let Some(guardian) = args.guardian {
+            details.push(("guardian", guardian.to_string()));
+        }
+        if let Some(policies) = args.proposal_config_policies {
+            let policies = policies
+                .iter()
+                .map(|policy| {
+                    let max_lamports = policy
+                        .max_lamports
+                        .map_or("unlimited".to_string(), |max| max.to_string());
+                    let token_caps = policy
+                        .max_token_amounts
+                        .iter()
+                        .map(|cap| format!(", max {} of {}", cap.max_amount, cap.mint))
+                        .collect::<String>();
+                    format!(
+                        "{} (signal only: {}, max lamports: {max_lamports}{token_caps}, min delay: {}s)",
+                        policy.proposal_config, policy.signal_only, policy.min_delay
+                    )
+                })
+                .collect::<Vec<_>>();
+            details.push(("proposal_config_policies", policies.join(", ")));
+        }
+    } else if discriminator == org_nft_guard::instruction::UpdateGuardV0::DISCRIMINATOR {
+        let args = org_nft_guard::instruction::UpdateGuardV0::deserialize(args)
+            .ok()?
+            .args;
+        if let Some(authority) = args.authority {
+            details.push(("authority", authority.to_string()));
+        }
+        if let Some(min_weight) = args.min_weight {
+            details.push(("min_weight", min_weight.to_string()));
+        }
+        if args.guard_type.is_some() {
+            details.push(("guard_type", "replaced".to_string()));
+        }
+    }
+
+    Some(details)
+}
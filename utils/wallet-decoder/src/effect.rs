@@ -0,0 +1,250 @@
+use anchor_lang::prelude::Pubkey;
+use std::fmt;
+
+/// What a single instruction or action of a choice transaction does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Effect {
+    TransferLamports {
+        from: Pubkey,
+        to: Pubkey,
+        lamports: u64,
+    },
+    TransferTokens {
+        source: Pubkey,
+        destination: Pubkey,
+        /// `None` when the authority is not an account of the instruction and the source
+        /// token account is not in the snapshot
+        authority: Option<Pubkey>,
+        mint: Option<Pubkey>,
+        amount: u64,
+        decimals: Option<u8>,
+    },
+    MintTokens {
+        mint: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        decimals: Option<u8>,
+    },
+    BurnTokens {
+        account: Pubkey,
+        mint: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        decimals: Option<u8>,
+    },
+    /// Lets `delegate` move up to `amount` out of the token account
+    ApproveTokens {
+        account: Pubkey,
+        delegate: Pubkey,
+        owner: Pubkey,
+        mint: Option<Pubkey>,
+        amount: u64,
+        decimals: Option<u8>,
+    },
+    RevokeTokens {
+        account: Pubkey,
+        owner: Pubkey,
+    },
+    CreateAccount {
+        payer: Pubkey,
+        account: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        space: u64,
+    },
+    CreateAssociatedTokenAccount {
+        payer: Pubkey,
+        account: Pubkey,
+        wallet: Pubkey,
+        mint: Pubkey,
+    },
+    CloseTokenAccount {
+        account: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+    },
+    SetTokenAuthority {
+        account: Pubkey,
+        authority_type: String,
+        new_authority: Option<Pubkey>,
+    },
+    /// An instruction of a known program without a dedicated summary
+    Instruction {
+        program: &'static str,
+        instruction: String,
+        accounts: Vec<Pubkey>,
+        /// Notable arguments, as (name, value)
+        details: Vec<(&'static str, String)>,
+    },
+    Unknown {
+        program_id: Pubkey,
+        accounts: Vec<Pubkey>,
+        data: Vec<u8>,
+    },
+}
+
+/// Formats `amount` of base units with `decimals`, or as base units when unknown
+pub fn format_amount(amount: u64, decimals: Option<u8>) -> String {
+    match decimals {
+        Some(decimals) if decimals > 0 => {
+            let unit = 10u128.pow(decimals as u32);
+            let whole = amount as u128 / unit;
+            let fraction = amount as u128 % unit;
+            let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+            let fraction = fraction.trim_end_matches('0');
+            if fraction.is_empty() {
+                whole.to_string()
+            } else {
+                format!("{whole}.{fraction}")
+            }
+        }
+        Some(_) => amount.to_string(),
+        None => format!("{amount} base units"),
+    }
+}
+
+fn format_mint(mint: &Option<Pubkey>) -> String {
+    mint.map_or("of an unknown mint".to_string(), |mint| {
+        format!("of {mint}")
+    })
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Effect::TransferLamports { from, to, lamports } => write!(
+                f,
+                "Transfer {} SOL from {from} to {to}",
+                format_amount(*lamports, Some(9))
+            ),
+            Effect::TransferTokens {
+                source,
+                destination,
+                authority,
+                mint,
+                amount,
+                decimals,
+            } => {
+                write!(
+                    f,
+                    "Transfer {} tokens {} from {source} to {destination}",
+                    format_amount(*amount, *decimals),
+                    format_mint(mint)
+                )?;
+                if let Some(authority) = authority {
+                    write!(f, ", signed by {authority}")?;
+                }
+                Ok(())
+            }
+            Effect::MintTokens {
+                mint,
+                destination,
+                authority,
+                amount,
+                decimals,
+            } => write!(
+                f,
+                "Mint {} tokens of {mint} to {destination}, signed by {authority}",
+                format_amount(*amount, *decimals)
+            ),
+            Effect::BurnTokens {
+                account,
+                mint,
+                authority,
+                amount,
+                decimals,
+            } => write!(
+                f,
+                "Burn {} tokens of {mint} from {account}, signed by {authority}",
+                format_amount(*amount, *decimals)
+            ),
+            Effect::ApproveTokens {
+                account,
+                delegate,
+                owner,
+                mint,
+                amount,
+                decimals,
+            } => write!(
+                f,
+                "Allow {delegate} to spend {} tokens {} from {account}, signed by {owner}",
+                format_amount(*amount, *decimals),
+                format_mint(mint)
+            ),
+            Effect::RevokeTokens { account, owner } => write!(
+                f,
+                "Revoke the delegate of {account}, signed by {owner}"
+            ),
+            Effect::CreateAccount {
+                payer,
+                account,
+                owner,
+                lamports,
+                space,
+            } => write!(
+                f,
+                "Create account {account} of {space} bytes owned by {owner}, funded with {} SOL by {payer}",
+                format_amount(*lamports, Some(9))
+            ),
+            Effect::CreateAssociatedTokenAccount {
+                payer,
+                account,
+                wallet,
+                mint,
+            } => write!(
+                f,
+                "Create token account {account} of {mint} for {wallet}, paid by {payer}"
+            ),
+            Effect::CloseTokenAccount {
+                account,
+                destination,
+                authority,
+            } => write!(
+                f,
+                "Close token account {account}, sending its rent to {destination}, signed by {authority}"
+            ),
+            Effect::SetTokenAuthority {
+                account,
+                authority_type,
+                new_authority,
+            } => match new_authority {
+                Some(new_authority) => write!(
+                    f,
+                    "Set the {authority_type} authority of {account} to {new_authority}"
+                ),
+                None => write!(f, "Remove the {authority_type} authority of {account}"),
+            },
+            Effect::Instruction {
+                program,
+                instruction,
+                accounts,
+                details,
+            } => {
+                write!(f, "{program}: {instruction}")?;
+                for (name, value) in details {
+                    write!(f, "\n  {name}: {value}")?;
+                }
+                for (index, account) in accounts.iter().enumerate() {
+                    write!(f, "\n  account {index}: {account}")?;
+                }
+                Ok(())
+            }
+            Effect::Unknown {
+                program_id,
+                accounts,
+                data,
+            } => {
+                write!(
+                    f,
+                    "Unknown instruction of program {program_id} with {} bytes of data",
+                    data.len()
+                )?;
+                for (index, account) in accounts.iter().enumerate() {
+                    write!(f, "\n  account {index}: {account}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::Pubkey;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to read snapshot: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid snapshot account file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid account data: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("unsupported account data encoding {0}, expected base64")]
+    UnsupportedEncoding(String),
+    #[error("invalid pubkey {0}")]
+    InvalidPubkey(String),
+    #[error("account {0} is missing from the snapshot")]
+    MissingAccount(Pubkey),
+    #[error("account index {0} is out of bounds for the transaction")]
+    InvalidIndex(u8),
+    #[error("transaction {0} does not match its hash")]
+    HashMismatch(Pubkey),
+}
@@ -0,0 +1,12 @@
+//! Decodes the transactions queued on organization wallet proposals into summaries of
+//! their effects, so voters can review a choice without reading instruction bytes.
+
+pub mod decode;
+pub mod effect;
+pub mod error;
+pub mod snapshot;
+
+pub use decode::*;
+pub use effect::*;
+pub use error::*;
+pub use snapshot::*;
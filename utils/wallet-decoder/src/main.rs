@@ -0,0 +1,83 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context};
+use organization_wallet::state::ExecutionWindowV0;
+use proposal::ProposalV0;
+use std::{path::Path, str::FromStr};
+use wallet_decoder::{decode_transaction, verify_transaction_hash, Snapshot};
+
+const USAGE: &str = "usage: wallet-decoder <SNAPSHOT_DIR> <PROPOSAL>
+
+Prints the effects of every choice transaction of PROPOSAL. SNAPSHOT_DIR holds one
+file per account, as written by `solana account <pubkey> --output json`. It must
+contain the choice transactions. The proposal, mints and token accounts are used to
+name choices and format amounts when present.";
+
+fn format_window(window: &ExecutionWindowV0) -> String {
+    match window {
+        ExecutionWindowV0::AfterResolution { delay, expiry } => match expiry {
+            Some(expiry) => format!("{delay}s to {expiry}s after resolution"),
+            None => format!("{delay}s after resolution, never expires"),
+        },
+        ExecutionWindowV0::Absolute { from, until } => match until {
+            Some(until) => format!("from {from} until {until}"),
+            None => format!("from {from}, never expires"),
+        },
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let [snapshot_dir, proposal] = args.as_slice() else {
+        bail!(USAGE);
+    };
+    let proposal =
+        Pubkey::from_str(proposal).map_err(|_| anyhow!("invalid proposal {proposal}"))?;
+    let snapshot = Snapshot::load(Path::new(snapshot_dir))
+        .with_context(|| format!("failed to load snapshot from {snapshot_dir}"))?;
+    let choices = snapshot
+        .deserialize::<ProposalV0>(&proposal)
+        .map(|proposal| proposal.choices)
+        .unwrap_or_default();
+
+    let transactions = snapshot.choice_transactions(&proposal);
+    if transactions.is_empty() {
+        println!("No choice transactions of {proposal} in the snapshot");
+    }
+
+    let mut current_choice = None;
+    for (key, choice_transaction) in transactions {
+        let choice = (
+            choice_transaction.organization_wallet,
+            choice_transaction.choice_index,
+        );
+        if current_choice != Some(choice) {
+            current_choice = Some(choice);
+            let name = choices
+                .get(choice_transaction.choice_index as usize)
+                .map(|choice| format!(" \"{}\"", choice.name))
+                .unwrap_or_default();
+            println!(
+                "\nChoice {}{name} of wallet {}",
+                choice_transaction.choice_index, choice_transaction.organization_wallet
+            );
+        }
+
+        println!(
+            "  Transaction {} ({key}), executable {}",
+            choice_transaction.transaction_index,
+            format_window(&choice_transaction.execution_window)
+        );
+        if !choice_transaction.finalized {
+            println!("    Not finalized, data may still be appended");
+        }
+        verify_transaction_hash(&key, &choice_transaction)?;
+        let effects = decode_transaction(&choice_transaction.transaction, &snapshot)
+            .with_context(|| format!("failed to decode transaction {key}"))?;
+        for (index, effect) in effects.iter().enumerate() {
+            let effect = effect.to_string().replace('\n', "\n      ");
+            println!("    {}. {effect}", index + 1);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use crate::error::Error;
+use anchor_lang::{prelude::*, AccountDeserialize, Discriminator};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use organization_wallet::state::ChoiceTransactionV0;
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
+
+pub struct SnapshotAccount {
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+/// Accounts read from a local snapshot, used to resolve mints, token accounts and proposals
+#[derive(Default)]
+pub struct Snapshot {
+    accounts: HashMap<Pubkey, SnapshotAccount>,
+}
+
+/// Format of `solana account <pubkey> --output json`
+#[derive(Deserialize)]
+struct AccountFile {
+    pubkey: String,
+    account: AccountFileData,
+}
+
+#[derive(Deserialize)]
+struct AccountFileData {
+    lamports: u64,
+    data: (String, String),
+    owner: String,
+}
+
+fn parse_pubkey(key: &str) -> std::result::Result<Pubkey, Error> {
+    Pubkey::from_str(key).map_err(|_| Error::InvalidPubkey(key.to_string()))
+}
+
+impl Snapshot {
+    /// Loads every `.json` file in `dir`, each holding one account as written by
+    /// `solana account <pubkey> --output json`
+    pub fn load(dir: &Path) -> std::result::Result<Self, Error> {
+        let mut snapshot = Snapshot::default();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+
+            let file: AccountFile = serde_json::from_slice(&fs::read(&path)?)?;
+            let (data, encoding) = file.account.data;
+            if encoding != "base64" {
+                return Err(Error::UnsupportedEncoding(encoding));
+            }
+            snapshot.insert(
+                parse_pubkey(&file.pubkey)?,
+                SnapshotAccount {
+                    owner: parse_pubkey(&file.account.owner)?,
+                    lamports: file.account.lamports,
+                    data: STANDARD.decode(data)?,
+                },
+            );
+        }
+
+        Ok(snapshot)
+    }
+
+    pub fn insert(&mut self, key: Pubkey, account: SnapshotAccount) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn get(&self, key: &Pubkey) -> Option<&SnapshotAccount> {
+        self.accounts.get(key)
+    }
+
+    pub fn deserialize<T: AccountDeserialize>(&self, key: &Pubkey) -> Option<T> {
+        self.get(key)
+            .and_then(|account| T::try_deserialize(&mut &account.data[..]).ok())
+    }
+
+    pub fn mint_decimals(&self, mint: &Pubkey) -> Option<u8> {
+        self.deserialize::<Mint>(mint).map(|mint| mint.decimals)
+    }
+
+    pub fn token_account(&self, key: &Pubkey) -> Option<TokenAccount> {
+        self.deserialize::<TokenAccount>(key)
+    }
+
+    /// Choice transactions of `proposal`, ordered by choice then transaction index
+    pub fn choice_transactions(&self, proposal: &Pubkey) -> Vec<(Pubkey, ChoiceTransactionV0)> {
+        let mut transactions = self
+            .accounts
+            .iter()
+            .filter(|(_, account)| {
+                account.owner == organization_wallet::ID
+                    && account
+                        .data
+                        .starts_with(&ChoiceTransactionV0::DISCRIMINATOR)
+            })
+            .filter_map(|(key, _)| Some((*key, self.deserialize::<ChoiceTransactionV0>(key)?)))
+            .filter(|(_, transaction)| transaction.proposal == *proposal)
+            .collect::<Vec<_>>();
+        transactions.sort_by_key(|(_, transaction)| {
+            (
+                transaction.organization_wallet,
+                transaction.choice_index,
+                transaction.transaction_index,
+            )
+        });
+
+        transactions
+    }
+}
@@ -0,0 +1,379 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, program_pack::Pack, system_instruction},
+    InstructionData,
+};
+use anchor_spl::{
+    associated_token::spl_associated_token_account::{
+        get_associated_token_address, instruction::create_associated_token_account,
+    },
+    token::spl_token,
+    token_2022::spl_token_2022,
+};
+use organization_wallet::state::{
+    ChoiceTransactionV0, CompiledInstructionV0, CompiledTransactionV0,
+};
+use wallet_decoder::{
+    decode_instruction, decode_transaction, verify_transaction_hash, Effect, Error, Snapshot,
+    SnapshotAccount,
+};
+
+fn decode(ix: Instruction, snapshot: &Snapshot) -> Effect {
+    let accounts = ix
+        .accounts
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect::<Vec<_>>();
+    decode_instruction(&ix.program_id, &accounts, &ix.data, snapshot)
+}
+
+/// A snapshot holding a token account of `mint` with 6 decimals
+fn token_snapshot(token_account: Pubkey, mint: Pubkey, owner: Pubkey) -> Snapshot {
+    let mut snapshot = Snapshot::default();
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account::pack(
+        spl_token::state::Account {
+            mint,
+            owner,
+            amount: 100,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    snapshot.insert(
+        token_account,
+        SnapshotAccount {
+            owner: spl_token::ID,
+            lamports: 0,
+            data,
+        },
+    );
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint::pack(
+        spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    snapshot.insert(
+        mint,
+        SnapshotAccount {
+            owner: spl_token::ID,
+            lamports: 0,
+            data,
+        },
+    );
+
+    snapshot
+}
+
+#[test]
+fn decodes_system_instructions() {
+    let (from, to, owner) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let snapshot = Snapshot::default();
+
+    assert_eq!(
+        decode(system_instruction::transfer(&from, &to, 5), &snapshot),
+        Effect::TransferLamports {
+            from,
+            to,
+            lamports: 5
+        }
+    );
+    assert_eq!(
+        decode(
+            system_instruction::create_account(&from, &to, 10, 165, &owner),
+            &snapshot
+        ),
+        Effect::CreateAccount {
+            payer: from,
+            account: to,
+            owner,
+            lamports: 10,
+            space: 165,
+        }
+    );
+}
+
+#[test]
+fn decodes_token_transfers() {
+    let (source, destination, owner, mint) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let snapshot = token_snapshot(source, mint, owner);
+
+    assert_eq!(
+        decode(
+            spl_token::instruction::transfer(&spl_token::ID, &source, &destination, &owner, &[], 7)
+                .unwrap(),
+            &snapshot
+        ),
+        Effect::TransferTokens {
+            source,
+            destination,
+            authority: Some(owner),
+            mint: Some(mint),
+            amount: 7,
+            decimals: Some(6),
+        }
+    );
+    assert_eq!(
+        decode(
+            spl_token_2022::instruction::transfer_checked(
+                &spl_token_2022::ID,
+                &source,
+                &mint,
+                &destination,
+                &owner,
+                &[],
+                7,
+                6
+            )
+            .unwrap(),
+            &Snapshot::default()
+        ),
+        Effect::TransferTokens {
+            source,
+            destination,
+            authority: Some(owner),
+            mint: Some(mint),
+            amount: 7,
+            decimals: Some(6),
+        }
+    );
+}
+
+#[test]
+fn decodes_token_approvals() {
+    let (source, delegate, owner, mint) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let snapshot = token_snapshot(source, mint, owner);
+    let approved = Effect::ApproveTokens {
+        account: source,
+        delegate,
+        owner,
+        mint: Some(mint),
+        amount: 50,
+        decimals: Some(6),
+    };
+    let revoked = Effect::RevokeTokens {
+        account: source,
+        owner,
+    };
+
+    assert_eq!(
+        decode(
+            spl_token::instruction::approve(&spl_token::ID, &source, &delegate, &owner, &[], 50)
+                .unwrap(),
+            &snapshot
+        ),
+        approved
+    );
+    assert_eq!(
+        decode(
+            spl_token::instruction::approve_checked(
+                &spl_token::ID,
+                &source,
+                &mint,
+                &delegate,
+                &owner,
+                &[],
+                50,
+                6
+            )
+            .unwrap(),
+            &Snapshot::default()
+        ),
+        approved
+    );
+    assert_eq!(
+        decode(
+            spl_token::instruction::revoke(&spl_token::ID, &source, &owner, &[]).unwrap(),
+            &snapshot
+        ),
+        revoked
+    );
+
+    assert_eq!(
+        decode(
+            spl_token_2022::instruction::approve(
+                &spl_token_2022::ID,
+                &source,
+                &delegate,
+                &owner,
+                &[],
+                50
+            )
+            .unwrap(),
+            &snapshot
+        ),
+        approved
+    );
+    assert_eq!(
+        decode(
+            spl_token_2022::instruction::approve_checked(
+                &spl_token_2022::ID,
+                &source,
+                &mint,
+                &delegate,
+                &owner,
+                &[],
+                50,
+                6
+            )
+            .unwrap(),
+            &Snapshot::default()
+        ),
+        approved
+    );
+    assert_eq!(
+        decode(
+            spl_token_2022::instruction::revoke(&spl_token_2022::ID, &source, &owner, &[]).unwrap(),
+            &snapshot
+        ),
+        revoked
+    );
+}
+
+#[test]
+fn decodes_associated_token_accounts() {
+    let (payer, wallet, mint) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    assert_eq!(
+        decode(
+            create_associated_token_account(&payer, &wallet, &mint, &spl_token::ID),
+            &Snapshot::default()
+        ),
+        Effect::CreateAssociatedTokenAccount {
+            payer,
+            account: get_associated_token_address(&wallet, &mint),
+            wallet,
+            mint,
+        }
+    );
+}
+
+#[test]
+fn decodes_anchor_instructions() {
+    let authority = Pubkey::new_unique();
+    let snapshot = Snapshot::default();
+
+    let data = organization::instruction::UpdateOrganizationV0 {
+        args: organization::instructions::UpdateOrganizationArgsV0 {
+            authority: Some(authority),
+            default_proposal_config: None,
+            proposal_program: None,
+            uri: None,
+            guard: None,
+        },
+    }
+    .data();
+    assert_eq!(
+        decode_instruction(&organization::ID, &[], &data, &snapshot),
+        Effect::Instruction {
+            program: "Organization",
+            instruction: "update_organization_v0".to_string(),
+            accounts: vec![],
+            details: vec![("authority", authority.to_string())],
+        }
+    );
+
+    let data = organization_wallet::instruction::VetoTransactionV0 {}.data();
+    assert_eq!(
+        decode_instruction(&organization_wallet::ID, &[authority], &data, &snapshot),
+        Effect::Instruction {
+            program: "Organization Wallet",
+            instruction: "veto_transaction_v0".to_string(),
+            accounts: vec![authority],
+            details: vec![],
+        }
+    );
+
+    let data = org_nft_guard::instruction::CloseGuardV0 {}.data();
+    assert_eq!(
+        decode_instruction(&org_nft_guard::ID, &[], &data, &snapshot),
+        Effect::Instruction {
+            program: "Org Guard",
+            instruction: "close_guard_v0".to_string(),
+            accounts: vec![],
+            details: vec![],
+        }
+    );
+
+    // Unknown discriminators are not attributed to the program
+    let program_id = organization_wallet::ID;
+    assert_eq!(
+        decode_instruction(&program_id, &[], &[0; 8], &snapshot),
+        Effect::Unknown {
+            program_id,
+            accounts: vec![],
+            data: vec![0; 8],
+        }
+    );
+}
+
+#[test]
+fn decodes_accounts_loaded_from_lookup_tables() {
+    let (wallet, recipient) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let transaction = CompiledTransactionV0 {
+        num_rw_signers: 1,
+        accounts: vec![wallet, anchor_lang::system_program::ID],
+        instructions: vec![CompiledInstructionV0 {
+            program_id_index: 1,
+            accounts: vec![0, 2],
+            data: system_instruction::transfer(&wallet, &recipient, 5).data,
+        }],
+        loaded_writable: vec![recipient],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        decode_transaction(&transaction, &Snapshot::default()).unwrap(),
+        vec![Effect::TransferLamports {
+            from: wallet,
+            to: recipient,
+            lamports: 5
+        }]
+    );
+}
+
+#[test]
+fn verifies_transaction_hashes() {
+    let key = Pubkey::new_unique();
+    let mut choice_transaction = ChoiceTransactionV0 {
+        transaction: CompiledTransactionV0 {
+            accounts: vec![Pubkey::new_unique()],
+            ..Default::default()
+        },
+        finalized: true,
+        ..Default::default()
+    };
+    choice_transaction.transaction_hash = choice_transaction.transaction.hash().unwrap();
+    assert!(verify_transaction_hash(&key, &choice_transaction).is_ok());
+
+    choice_transaction.transaction.loaded_readonly = vec![Pubkey::new_unique()];
+    assert!(matches!(
+        verify_transaction_hash(&key, &choice_transaction),
+        Err(Error::HashMismatch(mismatched)) if mismatched == key
+    ));
+}